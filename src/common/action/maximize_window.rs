@@ -1,9 +1,30 @@
 use crate::common::{enums::WindowState, traits::Window};
+use crate::Result;
 
-pub fn implement_maximize_action_on_window(foreground_window: Box<dyn Window>) {
-    let window_state = foreground_window.get_state();
+pub fn implement_maximize_action_on_window(foreground_window: Box<dyn Window>) -> Result<()> {
+    let window_state = foreground_window.get_state()?;
     match window_state {
         WindowState::Maximized => foreground_window.restore(),
         _ => foreground_window.maximize(),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::common::{
+        config::Config, enums::WindowState, hotkey_action::HotKeyAction, logic::run_single_action,
+    };
+    use crate::fake::desktop::FakeDesktop;
+
+    #[test]
+    fn maximizes_and_restores() {
+        let (desktop, window) = FakeDesktop::with_monitor_and_window();
+        let config = Config::default();
+        run_single_action(&desktop, &config, HotKeyAction::MaximizeWindow).unwrap();
+        assert_eq!(desktop.window(window).state, WindowState::Maximized);
+        assert_eq!(desktop.window(window).rect.width(), 1920);
+        run_single_action(&desktop, &config, HotKeyAction::MaximizeWindow).unwrap();
+        assert_eq!(desktop.window(window).state, WindowState::Normal);
+        assert_eq!(desktop.window(window).rect.width(), 800);
+    }
+}
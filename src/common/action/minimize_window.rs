@@ -1,9 +1,28 @@
 use crate::common::{enums::WindowState, traits::Window};
+use crate::Result;
 
-pub fn implement_minimize_action_on_window(foreground_window: Box<dyn Window>) {
-    let window_state = foreground_window.get_state();
+pub fn implement_minimize_action_on_window(foreground_window: Box<dyn Window>) -> Result<()> {
+    let window_state = foreground_window.get_state()?;
     match window_state {
         WindowState::Minimized => foreground_window.restore(),
         _ => foreground_window.minimize(),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::common::{
+        config::Config, enums::WindowState, hotkey_action::HotKeyAction, logic::run_single_action,
+    };
+    use crate::fake::desktop::FakeDesktop;
+
+    #[test]
+    fn minimizes_and_restores() {
+        let (desktop, window) = FakeDesktop::with_monitor_and_window();
+        let config = Config::default();
+        run_single_action(&desktop, &config, HotKeyAction::MinimizeWindow).unwrap();
+        assert_eq!(desktop.window(window).state, WindowState::Minimized);
+        run_single_action(&desktop, &config, HotKeyAction::MinimizeWindow).unwrap();
+        assert_eq!(desktop.window(window).state, WindowState::Normal);
+    }
+}
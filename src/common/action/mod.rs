@@ -2,4 +2,7 @@ pub mod maximize_window;
 pub mod minimize_window;
 pub mod move_window;
 pub mod move_window_to_another_screen;
+pub mod move_window_to_grid_cell;
+pub mod move_window_to_zone;
 pub mod resize_window;
+pub mod resize_window_history_aware;
@@ -1,15 +1,117 @@
 use crate::common::{
-    calculation::calculate_window_rect::calculate_window_rect, hotkey_action::HotKeyAction,
-    traits::Window,
+    calculation::calculate_window_rect::{
+        calculate_window_rect, calculate_window_rect_with_fraction,
+    },
+    config::Config,
+    hotkey_action::HotKeyAction,
+    traits::{Desktop, Window},
 };
+use crate::Result;
 
 // TODO: Change the commented printLns into log.debugs where apropriate
-pub fn implement_move_action_on_window(foreground_window: Box<dyn Window>, action: HotKeyAction) {
-    let monitor_rect = foreground_window.get_current_monitor().get_size();
+pub fn implement_move_action_on_window(
+    foreground_window: &dyn Window,
+    system: &dyn Desktop,
+    config: &Config,
+    action: HotKeyAction,
+    size_fraction: Option<f32>,
+) -> Result<()> {
+    let monitor = foreground_window.get_current_monitor()?;
+    let monitor_rect = monitor.get_size()?;
+    let gaps = config.get_gaps(system.get_monitor_index(monitor.as_ref(), config.monitor_order)?);
     //println!("{:?} {:?}", monitor_info, action);
-    let window_margin = foreground_window.get_margin();
-    let target_rect = calculate_window_rect(&monitor_rect, &window_margin, action);
-    foreground_window.disable_snapping();
+    let window_margin = foreground_window.get_margin()?;
+    let target_rect = match size_fraction {
+        Some(fraction) => calculate_window_rect_with_fraction(
+            &monitor_rect,
+            &window_margin,
+            &gaps,
+            action,
+            fraction,
+        ),
+        None => calculate_window_rect(&monitor_rect, &window_margin, &gaps, action),
+    };
+    foreground_window.disable_snapping()?;
     //println!("implement_move_action_on_window: {:?}", target_rect);
     foreground_window.move_window(&target_rect)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::{
+        enums::WindowState, logic::run_single_action, structs::Rect, structs::WindowBorderSize,
+    };
+    use crate::fake::{desktop::FakeCall, desktop::FakeDesktop};
+    use crate::Error;
+
+    #[test]
+    fn moves_visible_part_of_window_into_place() {
+        let (desktop, window) = FakeDesktop::with_monitor_and_window();
+        // Invisible borders like on Windows 10
+        desktop.set_window_margin(
+            window,
+            WindowBorderSize {
+                left: -7,
+                right: 7,
+                top: 0,
+                bottom: 7,
+            },
+        );
+        desktop.set_window_state(window, WindowState::Maximized);
+
+        run_single_action(
+            &desktop,
+            &Config::default(),
+            HotKeyAction::MoveWindowToLeftMiddle,
+        )
+        .unwrap();
+        let target = Rect {
+            left: -7,
+            right: 968,
+            top: 0,
+            bottom: 1049,
+        };
+        assert_eq!(desktop.window(window).state, WindowState::Normal);
+        assert_eq!(desktop.window(window).rect, target);
+        // The position the window was left at is recorded in the window history
+        assert!(desktop.take_calls().ends_with(&[
+            FakeCall::DisableSnapping { window },
+            FakeCall::MoveWindow {
+                window,
+                rect: target
+            },
+            FakeCall::GetPosition { window },
+        ]));
+    }
+
+    #[test]
+    fn aborts_when_the_window_was_closed() {
+        let (desktop, window) = FakeDesktop::with_monitor_and_window();
+        let foreground_window = desktop.get_foreground_window().unwrap();
+        desktop.close_window(window);
+
+        assert_eq!(
+            implement_move_action_on_window(
+                foreground_window.as_ref(),
+                &desktop,
+                &Config::default(),
+                HotKeyAction::MoveWindowToLeftMiddle,
+                None,
+            ),
+            Err(Error::WindowNotFound(window))
+        );
+        assert!(!desktop
+            .take_calls()
+            .iter()
+            .any(|call| matches!(call, FakeCall::MoveWindow { .. })));
+        assert_eq!(
+            run_single_action(
+                &desktop,
+                &Config::default(),
+                HotKeyAction::MoveWindowToLeftMiddle
+            ),
+            Err(Error::NoForegroundWindow)
+        );
+    }
+}
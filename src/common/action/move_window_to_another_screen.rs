@@ -1,9 +1,13 @@
 use crate::common::{
+    calculation::calculate_window_rect::{calculate_window_rect, find_window_zone},
+    config::Config,
     enums::WindowState,
     hotkey_action::HotKeyAction,
-    structs::{Rect, WindowPosition},
-    traits::{Desktop, Window},
+    monitor_topology::{Direction, MonitorTopology},
+    structs::{MonitorTarget, Rect, WindowPosition},
+    traits::{Desktop, Monitor, Window},
 };
+use crate::Result;
 
 // TODO: Still requires some tweaking in values
 // TODO: Possibly use min percentage limit to connect to screen edges. Cheating, but outcome might be what we want
@@ -13,45 +17,82 @@ use crate::common::{
 pub fn implement_move_action_to_another_screen(
     foreground_window: Box<dyn Window>,
     system: &dyn Desktop,
-    _action: HotKeyAction,
-) {
-    let monitor_boxes = system.get_all_monitors();
-    let mut all_monitors = vec![];
-    for i in &monitor_boxes {
-        all_monitors.push(i.as_ref());
-    }
+    config: &Config,
+    action: HotKeyAction,
+) -> Result<()> {
+    let monitor_boxes = system.get_ordered_monitors(config.monitor_order)?;
+    let all_monitors: Vec<&dyn Monitor> = monitor_boxes.iter().map(|m| m.as_ref()).collect();
+    let current_monitor = foreground_window.get_current_monitor()?;
 
-    if all_monitors.len() == 1 {
-        return;
-    }
+    let target_monitor =
+        match select_target_monitor(&all_monitors, current_monitor.as_ref(), action)? {
+            Some(target_monitor) => target_monitor,
+            None => return Ok(()),
+        };
 
-    all_monitors.sort_by(|a, b| a.get_size().left.cmp(&b.get_size().left));
-    let current_monitor = foreground_window.get_current_monitor();
+    move_window_to_monitor(
+        foreground_window.as_ref(),
+        system,
+        config,
+        current_monitor.as_ref(),
+        target_monitor,
+    )
+}
 
-    // TODO: this could use some refactoring
-    let mut index = 0;
-    let mut found_index: i32 = -1;
-    for m in &all_monitors {
-        if m.equals(&current_monitor) {
-            found_index = index;
-            break;
+pub fn implement_move_action_to_monitor(
+    foreground_window: Box<dyn Window>,
+    system: &dyn Desktop,
+    config: &Config,
+    target: &MonitorTarget,
+) -> Result<()> {
+    let target_index = match target {
+        MonitorTarget::Index(index) => Some(*index),
+        MonitorTarget::Name(name) => config.get_monitor_index_by_name(name),
+    };
+    let ordered_monitors = system.get_ordered_monitors(config.monitor_order)?;
+    let target_monitor = match target_index.and_then(|index| ordered_monitors.get(index)) {
+        Some(target_monitor) => target_monitor,
+        None => {
+            log::warn!("No monitor found for {:?}", target);
+            return Ok(());
         }
-        index = index + 1;
+    };
+
+    let current_monitor = foreground_window.get_current_monitor()?;
+    if target_monitor.equals(current_monitor.as_ref()) {
+        return Ok(());
     }
-    let target_index: usize = (if found_index == 0 {
-        all_monitors.len() as i32 - 1
-    } else {
-        found_index - 1
-    }) as usize;
-    let target_monitor = &all_monitors[target_index];
+    move_window_to_monitor(
+        foreground_window.as_ref(),
+        system,
+        config,
+        current_monitor.as_ref(),
+        target_monitor.as_ref(),
+    )
+}
 
-    let window_state = foreground_window.get_state();
+// Moves the window keeping its position and size relative to the monitor.
+// Windows snapped into a zone are placed into the same zone on the target monitor.
+pub(crate) fn move_window_to_monitor(
+    foreground_window: &dyn Window,
+    system: &dyn Desktop,
+    config: &Config,
+    current_monitor: &dyn Monitor,
+    target_monitor: &dyn Monitor,
+) -> Result<()> {
+    let window_state = foreground_window.get_state()?;
     if window_state == WindowState::Maximized || window_state == WindowState::Minimized {
-        foreground_window.restore();
+        foreground_window.restore()?;
     }
 
-    let window_rect = foreground_window.get_position();
-    let current_monito_size = current_monitor.get_size();
+    let window_rect = foreground_window.get_position()?;
+    let current_monito_size = current_monitor.get_size()?;
+    let zone = find_window_zone(
+        &current_monito_size,
+        &window_rect,
+        &foreground_window.get_margin()?,
+        &config.get_gaps(system.get_monitor_index(current_monitor, config.monitor_order)?),
+    );
 
     let ratio_left: f32 = ((window_rect.left - current_monito_size.left) as f32
         / (current_monito_size.width()) as f32)
@@ -63,7 +104,7 @@ pub fn implement_move_action_to_another_screen(
     let ratio_height: f32 =
         (window_rect.height() as f32 / current_monito_size.height() as f32).abs();
 
-    let target_monitor_size = target_monitor.get_size();
+    let target_monitor_size = target_monitor.get_size()?;
     let new_left =
         (ratio_left * target_monitor_size.width() as f32) as i32 + target_monitor_size.left;
     let new_top =
@@ -78,18 +119,338 @@ pub fn implement_move_action_to_another_screen(
         height: new_height,
     };
     //println!("implement_move_action_to_another_screen: {:?}", target_rect);
-    foreground_window.move_window(&Rect::from(&target_rect));
+    foreground_window.move_window(&Rect::from(&target_rect))?;
 
     // Moving between monitors with diffrent DPI seems to result in different windows sizes in some cases.
     // Issuing the move command again is used as a workaround
-    if target_monitor.get_dpi_info() != current_monitor.get_dpi_info() {
-        foreground_window.move_window(&Rect::from(&target_rect));
+    if target_monitor.get_dpi_info()? != current_monitor.get_dpi_info()? {
+        foreground_window.move_window(&Rect::from(&target_rect))?;
+    }
+
+    // The margins may differ on the target monitor, so the zone is calculated only after the
+    // window has been moved there
+    if let Some(zone) = zone {
+        let zone_rect = calculate_window_rect(
+            &target_monitor_size,
+            &foreground_window.get_margin()?,
+            &config.get_gaps(system.get_monitor_index(target_monitor, config.monitor_order)?),
+            zone,
+        );
+        log::debug!(
+            "Keeping the window in its zone on the target monitor: {:?}",
+            zone_rect
+        );
+        foreground_window.move_window(&zone_rect)?;
     }
 
     // If the window was maximized or minimized when this function started, restore to that state
     match window_state {
         WindowState::Maximized => foreground_window.maximize(),
         WindowState::Minimized => foreground_window.minimize(),
-        _ => (),
+        _ => Ok(()),
+    }
+}
+
+/// Selects the monitor the window is moved to from `all_monitors`, given in the configured monitor
+/// order, using the monitor topology.
+///
+/// The non-continuous actions stop at the last monitor in the direction, the continuous ones
+/// wrap around to the other end. If there's no monitor to the left or right at all, eg. when the
+/// monitors are stacked, the continuous ones cycle through `all_monitors` instead. Returns `None`
+/// if the window shouldn't be moved.
+pub fn select_target_monitor<'a>(
+    all_monitors: &[&'a dyn Monitor],
+    current_monitor: &dyn Monitor,
+    action: HotKeyAction,
+) -> Result<Option<&'a dyn Monitor>> {
+    let current_index = match all_monitors.iter().position(|m| m.equals(current_monitor)) {
+        Some(current_index) => current_index,
+        None => return Ok(None),
     };
+    let monitor_rects = all_monitors
+        .iter()
+        .map(|m| m.get_size())
+        .collect::<Result<Vec<Rect>>>()?;
+    let topology = MonitorTopology::new(&monitor_rects);
+
+    let target_index = match action {
+        HotKeyAction::MoveWindowToLeftScreen => {
+            topology.get_neighbour(current_index, Direction::Left)
+        }
+        HotKeyAction::MoveWindowToRightScreen => {
+            topology.get_neighbour(current_index, Direction::Right)
+        }
+        HotKeyAction::MoveWindowToLeftScreenContinuous => topology
+            .get_neighbour_wrapping(current_index, Direction::Left)
+            .or_else(|| cycle(all_monitors.len(), current_index, -1)),
+        HotKeyAction::MoveWindowToRightScreenContinuous => topology
+            .get_neighbour_wrapping(current_index, Direction::Right)
+            .or_else(|| cycle(all_monitors.len(), current_index, 1)),
+        HotKeyAction::MoveWindowToUpperScreen => {
+            topology.get_neighbour(current_index, Direction::Up)
+        }
+        HotKeyAction::MoveWindowToLowerScreen => {
+            topology.get_neighbour(current_index, Direction::Down)
+        }
+        _ => None,
+    };
+
+    Ok(target_index.map(|target_index| all_monitors[target_index]))
+}
+
+// Index `step` away from `index` in a list of `count` monitors, wrapping around at both ends.
+// `None` if there's only one monitor.
+fn cycle(count: usize, index: usize, step: isize) -> Option<usize> {
+    if count < 2 {
+        return None;
+    }
+    Some((index as isize + step).rem_euclid(count as isize) as usize)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::logic::run_single_action;
+    use crate::common::structs::MonitorConfig;
+    use crate::fake::desktop::{FakeCall, FakeDesktop};
+    use crate::fake::monitor::FakeMonitor;
+
+    // Monitors given in non-sorted order: handle 1 is in the middle, 2 on the left, 3 on the right
+    fn monitors() -> Vec<FakeMonitor> {
+        vec![
+            FakeMonitor::full_hd_at(1, 0, 0),
+            FakeMonitor::full_hd_at(2, -1920, 0),
+            FakeMonitor::full_hd_at(3, 1920, 0),
+        ]
+    }
+
+    fn select(current: isize, action: HotKeyAction) -> Option<isize> {
+        let monitors = monitors();
+        let all_monitors: Vec<&dyn Monitor> = monitors.iter().map(|m| m as &dyn Monitor).collect();
+        let current_monitor = monitors.iter().find(|m| m.handle == current).unwrap();
+        select_target_monitor(&all_monitors, current_monitor, action)
+            .unwrap()
+            .map(|m| m.get_platform_specific_handle())
+    }
+
+    #[test]
+    fn moves_in_the_given_direction() {
+        assert_eq!(select(1, HotKeyAction::MoveWindowToLeftScreen), Some(2));
+        assert_eq!(select(1, HotKeyAction::MoveWindowToRightScreen), Some(3));
+        assert_eq!(
+            select(1, HotKeyAction::MoveWindowToLeftScreenContinuous),
+            Some(2)
+        );
+        assert_eq!(
+            select(1, HotKeyAction::MoveWindowToRightScreenContinuous),
+            Some(3)
+        );
+    }
+
+    #[test]
+    fn non_continuous_stops_at_last_monitor() {
+        assert_eq!(select(2, HotKeyAction::MoveWindowToLeftScreen), None);
+        assert_eq!(select(3, HotKeyAction::MoveWindowToRightScreen), None);
+    }
+
+    #[test]
+    fn continuous_wraps_around() {
+        assert_eq!(
+            select(2, HotKeyAction::MoveWindowToLeftScreenContinuous),
+            Some(3)
+        );
+        assert_eq!(
+            select(3, HotKeyAction::MoveWindowToRightScreenContinuous),
+            Some(2)
+        );
+    }
+
+    #[test]
+    fn single_monitor_is_not_changed() {
+        let monitor = FakeMonitor::full_hd_at(1, 0, 0);
+        let all_monitors: Vec<&dyn Monitor> = vec![&monitor];
+        assert!(select_target_monitor(
+            &all_monitors,
+            &monitor,
+            HotKeyAction::MoveWindowToLeftScreenContinuous
+        )
+        .unwrap()
+        .is_none());
+    }
+
+    #[test]
+    fn moves_up_and_down_between_stacked_monitors() {
+        let lower = FakeMonitor::full_hd_at(1, 0, 0);
+        let upper = FakeMonitor::full_hd_at(2, 0, -1080);
+        let all_monitors: Vec<&dyn Monitor> = vec![&lower, &upper];
+        let select_handle = |current: &dyn Monitor, action| {
+            select_target_monitor(&all_monitors, current, action)
+                .unwrap()
+                .map(|m| m.get_platform_specific_handle())
+        };
+        assert_eq!(
+            select_handle(&lower, HotKeyAction::MoveWindowToUpperScreen),
+            Some(2)
+        );
+        assert_eq!(
+            select_handle(&upper, HotKeyAction::MoveWindowToLowerScreen),
+            Some(1)
+        );
+        assert_eq!(
+            select_handle(&upper, HotKeyAction::MoveWindowToUpperScreen),
+            None
+        );
+        // Without monitors to the left or right, the continuous actions cycle through them
+        assert_eq!(
+            select_handle(&lower, HotKeyAction::MoveWindowToLeftScreenContinuous),
+            Some(2)
+        );
+        assert_eq!(
+            select_handle(&upper, HotKeyAction::MoveWindowToLeftScreenContinuous),
+            Some(1)
+        );
+        assert_eq!(
+            select_handle(&upper, HotKeyAction::MoveWindowToRightScreenContinuous),
+            Some(1)
+        );
+        assert_eq!(
+            select_handle(&lower, HotKeyAction::MoveWindowToLeftScreen),
+            None
+        );
+    }
+
+    #[test]
+    fn moves_window_keeping_its_relative_position() {
+        let desktop = FakeDesktop::with_monitors(monitors());
+        let window = desktop.add_window(Rect {
+            left: 100,
+            right: 900,
+            top: 200,
+            bottom: 800,
+        });
+        desktop.set_window_state(window, WindowState::Maximized);
+
+        // Stays maximized on the target monitor, restoring keeps the relative position
+        let config = Config::default();
+        run_single_action(&desktop, &config, HotKeyAction::MoveWindowToRightScreen).unwrap();
+        assert_eq!(desktop.window(window).state, WindowState::Maximized);
+        assert_eq!(
+            desktop.window(window).rect,
+            FakeMonitor::full_hd_at(3, 1920, 0).work_area
+        );
+        desktop.get_foreground_window().unwrap().restore().unwrap();
+        assert_eq!(
+            desktop.window(window).rect,
+            Rect {
+                left: 2020,
+                right: 2820,
+                top: 200,
+                bottom: 800,
+            }
+        );
+
+        // Already on the rightmost monitor
+        run_single_action(&desktop, &config, HotKeyAction::MoveWindowToRightScreen).unwrap();
+        assert_eq!(desktop.window(window).rect.left, 2020);
+    }
+
+    #[test]
+    fn window_in_a_zone_is_moved_into_the_same_zone_on_another_monitor() {
+        let desktop = FakeDesktop::with_monitors(monitors());
+        let window = desktop.add_window(Rect {
+            left: 100,
+            right: 900,
+            top: 200,
+            bottom: 800,
+        });
+        let config = Config::default();
+        run_single_action(&desktop, &config, HotKeyAction::MoveWindowToLeftTop).unwrap();
+
+        run_single_action(
+            &desktop,
+            &config,
+            HotKeyAction::MoveWindowToMonitor(MonitorTarget::Index(2)),
+        )
+        .unwrap();
+        assert_eq!(
+            desktop.window(window).rect,
+            Rect {
+                left: 1920,
+                right: 2881,
+                top: 0,
+                bottom: 540,
+            }
+        );
+    }
+
+    // Window on the middle monitor, the left monitor is called "left"
+    fn desktop_with_named_monitor() -> (FakeDesktop, isize, Config) {
+        let desktop = FakeDesktop::with_monitors(monitors());
+        let window = desktop.add_window(Rect {
+            left: 100,
+            right: 900,
+            top: 200,
+            bottom: 800,
+        });
+        let config = Config {
+            monitors: vec![MonitorConfig {
+                index: 0,
+                name: Some("left".to_string()),
+                ..MonitorConfig::default()
+            }],
+            ..Config::default()
+        };
+        (desktop, window, config)
+    }
+
+    fn move_to(desktop: &FakeDesktop, config: &Config, target: MonitorTarget) {
+        implement_move_action_to_monitor(
+            desktop.get_foreground_window().unwrap(),
+            desktop,
+            config,
+            &target,
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn moves_to_the_monitor_at_the_index() {
+        let (desktop, window, config) = desktop_with_named_monitor();
+        move_to(&desktop, &config, MonitorTarget::Index(2));
+        assert_eq!(
+            desktop.window(window).rect,
+            Rect {
+                left: 2020,
+                right: 2820,
+                top: 200,
+                bottom: 800,
+            }
+        );
+    }
+
+    #[test]
+    fn moves_to_the_monitor_with_the_name() {
+        let (desktop, window, config) = desktop_with_named_monitor();
+        move_to(&desktop, &config, MonitorTarget::Name("left".to_string()));
+        assert_eq!(
+            desktop.window(window).rect,
+            Rect {
+                left: -1820,
+                right: -1020,
+                top: 200,
+                bottom: 800,
+            }
+        );
+    }
+
+    #[test]
+    fn missing_monitor_leaves_the_window_in_place() {
+        let (desktop, _, config) = desktop_with_named_monitor();
+        move_to(&desktop, &config, MonitorTarget::Index(3));
+        move_to(&desktop, &config, MonitorTarget::Name("right".to_string()));
+        assert!(!desktop
+            .take_calls()
+            .iter()
+            .any(|call| matches!(call, FakeCall::MoveWindow { .. })));
+    }
 }
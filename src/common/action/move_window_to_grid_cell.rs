@@ -0,0 +1,76 @@
+use crate::common::{
+    calculation::calculate_window_rect::calculate_grid_cell_rect,
+    config::Config,
+    structs::GridCell,
+    traits::{Desktop, Window},
+};
+use crate::Result;
+
+pub fn implement_move_to_grid_cell_action_on_window(
+    foreground_window: &dyn Window,
+    system: &dyn Desktop,
+    cell: &GridCell,
+    config: &Config,
+) -> Result<()> {
+    let monitor = foreground_window.get_current_monitor()?;
+    let monitor_index = system.get_monitor_index(monitor.as_ref(), config.monitor_order)?;
+    let grid = config.get_grid_layout(monitor_index);
+    let gaps = config.get_gaps(monitor_index);
+    let window_margin = foreground_window.get_margin()?;
+    let target_rect =
+        calculate_grid_cell_rect(&monitor.get_size()?, &window_margin, &gaps, &grid, cell);
+    log::debug!(
+        "Moving window to {:?} of {:?}: {:?}",
+        cell,
+        grid,
+        target_rect
+    );
+    foreground_window.disable_snapping()?;
+    foreground_window.move_window(&target_rect)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::{
+        hotkey_action::HotKeyAction,
+        logic::run_single_action,
+        structs::{Rect, WindowBorderSize},
+    };
+    use crate::fake::desktop::FakeDesktop;
+
+    #[test]
+    fn moves_window_into_the_cell_of_the_default_grid() {
+        let (desktop, window) = FakeDesktop::with_monitor_and_window();
+        desktop.set_window_margin(
+            window,
+            WindowBorderSize {
+                left: -7,
+                right: 7,
+                top: 0,
+                bottom: 7,
+            },
+        );
+
+        run_single_action(
+            &desktop,
+            &Config::default(),
+            HotKeyAction::MoveWindowToGridCell(GridCell {
+                column: 1,
+                row: 1,
+                column_span: 1,
+                row_span: 1,
+            }),
+        )
+        .unwrap();
+        assert_eq!(
+            desktop.window(window).rect,
+            Rect {
+                left: 952,
+                right: 1927,
+                top: 520,
+                bottom: 1049,
+            }
+        );
+    }
+}
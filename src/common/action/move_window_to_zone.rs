@@ -0,0 +1,163 @@
+use crate::common::{
+    action::move_window_to_another_screen::move_window_to_monitor,
+    calculation::calculate_window_rect::calculate_zone_rect,
+    config::Config,
+    traits::{Desktop, Window},
+};
+use crate::Result;
+
+pub fn implement_move_to_zone_action_on_window(
+    foreground_window: &dyn Window,
+    system: &dyn Desktop,
+    zone_name: &str,
+    config: &Config,
+) -> Result<()> {
+    let current_monitor = foreground_window.get_current_monitor()?;
+    let current_index = system.get_monitor_index(current_monitor.as_ref(), config.monitor_order)?;
+    let zone = match config.find_zone(zone_name, current_index) {
+        Some(zone) => zone,
+        None => {
+            log::warn!("No zone called '{}' in the configuration", zone_name);
+            return Ok(());
+        }
+    };
+
+    // Zones of other monitors first move the window to that monitor, as the margins may differ
+    // there
+    let monitor_index = zone.monitor.or(current_index);
+    let monitor_rect = match zone.monitor {
+        Some(index) if Some(index) != current_index => {
+            match system
+                .get_ordered_monitors(config.monitor_order)?
+                .get(index)
+            {
+                Some(monitor) => {
+                    move_window_to_monitor(
+                        foreground_window,
+                        system,
+                        config,
+                        current_monitor.as_ref(),
+                        monitor.as_ref(),
+                    )?;
+                    monitor.get_size()?
+                }
+                None => {
+                    log::warn!("Zone '{}' refers to a missing monitor {}", zone_name, index);
+                    return Ok(());
+                }
+            }
+        }
+        _ => current_monitor.get_size()?,
+    };
+
+    foreground_window.disable_snapping()?;
+    let window_margin = foreground_window.get_margin()?;
+    let target_rect = calculate_zone_rect(
+        &monitor_rect,
+        &window_margin,
+        &config.get_gaps(monitor_index),
+        zone,
+    );
+    log::debug!("Moving window to zone '{}': {:?}", zone_name, target_rect);
+    foreground_window.move_window(&target_rect)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::{
+        enums::WindowState,
+        structs::{Rect, WindowBorderSize, Zone, ZoneValue},
+    };
+    use crate::fake::{desktop::FakeDesktop, monitor::FakeMonitor};
+
+    fn desktop() -> (FakeDesktop, isize) {
+        let (desktop, window) = FakeDesktop::with_monitor_and_window();
+        // Scaled to 150%, with wider invisible borders
+        desktop.add_monitor(FakeMonitor {
+            window_margin: Some(WindowBorderSize {
+                left: -11,
+                right: 11,
+                top: 0,
+                bottom: 11,
+            }),
+            ..FakeMonitor::new(
+                2,
+                Rect {
+                    left: 1920,
+                    right: 4480,
+                    top: 0,
+                    bottom: 1400,
+                },
+            )
+        });
+        desktop.set_window_margin(
+            window,
+            WindowBorderSize {
+                left: -7,
+                right: 7,
+                top: 0,
+                bottom: 7,
+            },
+        );
+        (desktop, window)
+    }
+
+    fn config() -> Config {
+        Config {
+            zones: vec![Zone {
+                name: "left".to_string(),
+                monitor: Some(1),
+                x: ZoneValue::Pixels(0),
+                y: ZoneValue::Pixels(0),
+                width: ZoneValue::Fraction(0.5),
+                height: ZoneValue::Fraction(1.0),
+            }],
+            ..Config::default()
+        }
+    }
+
+    fn move_to_zone(desktop: &FakeDesktop) {
+        implement_move_to_zone_action_on_window(
+            desktop.get_foreground_window().unwrap().as_ref(),
+            desktop,
+            "left",
+            &config(),
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn zone_on_another_monitor_uses_the_margin_there() {
+        let (desktop, window) = desktop();
+
+        move_to_zone(&desktop);
+        assert_eq!(
+            desktop.window(window).rect,
+            Rect {
+                left: 1909,
+                right: 3212,
+                top: 0,
+                bottom: 1413,
+            }
+        );
+    }
+
+    #[test]
+    fn maximized_window_is_moved_into_a_zone_on_another_monitor() {
+        let (desktop, window) = desktop();
+        desktop.get_foreground_window().unwrap().maximize().unwrap();
+
+        move_to_zone(&desktop);
+        assert_eq!(desktop.window(window).state, WindowState::Normal);
+        assert_eq!(
+            desktop.window(window).rect,
+            Rect {
+                left: 1909,
+                right: 3212,
+                top: 0,
+                bottom: 1413,
+            }
+        );
+    }
+}
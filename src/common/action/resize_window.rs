@@ -1,47 +1,76 @@
 use crate::common::{
-    hotkey_action::HotKeyAction,
+    calculation::calculate_resized_window_rect::calculate_resized_window_rect,
+    config::Config,
+    enums::ResizeDirection,
+    structs::ResizeStep,
     traits::{Desktop, Window},
 };
+use crate::Result;
 
 pub fn implement_resize_action_on_window(
-    _foreground_window: Box<dyn Window>,
-    _system: &dyn Desktop,
-    _action: HotKeyAction,
-) {
-    let monitor = _foreground_window.get_current_monitor();
-    let _monitor_size = monitor.get_size();
-    //println!("{:?} {:?}", _monitor, _action);
-    let _increase_amount_x = _monitor_size.width() as f32 * 0.1;
-    let _increase_amount_y = _monitor_size.height() as f32 * 0.1;
-
-    let _monitor_middle_point_x = _monitor_size.width() as f32 / 2.0;
-    let _monitor_middle_point_y = _monitor_size.height() as f32 / 2.0;
-
-    /*
-    let left = match action {
-        HotKeyAction::ChangeWindowSizeTowardsRightTop
-        | HotKeyAction::MoveWindowToRightMiddle
-        | HotKeyAction::MoveWindowToRightTop => (monitor_info.width / 2) - 1,
-        _ => 0,
-    } + monitor_info.x_offset;
-
-    let top = match action {
-        HotKeyAction::MoveWindowToLeftBottom
-        | HotKeyAction::MoveWindowToBottom
-        | HotKeyAction::MoveWindowToRightBottom => monitor_info.height / 2,
-        _ => 0,
-    } + monitor_info.y_offset;
+    foreground_window: Box<dyn Window>,
+    system: &dyn Desktop,
+    config: &Config,
+    direction: ResizeDirection,
+    step: ResizeStep,
+) -> Result<()> {
+    let monitor = foreground_window.get_current_monitor()?;
+    let monitor_rect = monitor.get_size()?;
+    let gaps = config.get_gaps(system.get_monitor_index(monitor.as_ref(), config.monitor_order)?);
+    let window_rect = foreground_window.get_position()?;
+    let window_margin = foreground_window.get_margin()?;
+    let target_rect = calculate_resized_window_rect(
+        &monitor_rect,
+        &window_rect,
+        &window_margin,
+        &gaps,
+        direction,
+        step,
+    );
+    foreground_window.disable_snapping()?;
+    foreground_window.move_window(&target_rect)
+}
 
-    let width = match action {
-        HotKeyAction::MoveWindowToBottom | HotKeyAction::MoveWindowToTop => monitor_info.width,
-        _ => (monitor_info.width / 2) + 1,
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::{
+        enums::WindowState, hotkey_action::HotKeyAction, logic::run_single_action, structs::Rect,
     };
+    use crate::fake::desktop::FakeDesktop;
 
-    let height = match action {
-        HotKeyAction::MoveWindowToLeftMiddle | HotKeyAction::MoveWindowToRightMiddle => {
-            monitor_info.height
+    fn rect(left: i32, right: i32, top: i32, bottom: i32) -> Rect {
+        Rect {
+            left,
+            right,
+            top,
+            bottom,
         }
-        _ => monitor_info.height / 2,
-    };
-    */
+    }
+
+    #[test]
+    fn maximized_window_shrinks_from_the_opposite_edge() {
+        let (desktop, window) = FakeDesktop::with_monitor_and_window();
+        desktop.get_foreground_window().unwrap().maximize().unwrap();
+        let config = Config::default();
+        let resize = |direction| {
+            run_single_action(
+                &desktop,
+                &config,
+                HotKeyAction::ResizeWindow {
+                    direction,
+                    step: ResizeStep::Pixels(100),
+                    history_aware: false,
+                },
+            )
+            .unwrap();
+            desktop.window(window).rect
+        };
+
+        // The maximized window already touches every edge of the monitor
+        assert_eq!(resize(ResizeDirection::Left), rect(0, 1820, 0, 1040));
+        assert_eq!(desktop.window(window).state, WindowState::Normal);
+        assert_eq!(resize(ResizeDirection::LeftTop), rect(0, 1720, 0, 940));
+        assert_eq!(resize(ResizeDirection::RightBottom), rect(0, 1820, 0, 1040));
+    }
 }
@@ -0,0 +1,223 @@
+use crate::common::{
+    calculation::calculate_resized_window_rect::{
+        calculate_neighbour_rect, calculate_resized_window_rect, get_visible_rect,
+        get_window_rect_from_visible, limit_growth_by_neighbour,
+    },
+    config::Config,
+    enums::{ResizeDirection, WindowState},
+    structs::{Rect, ResizeStep},
+    traits::{Desktop, Monitor, Window},
+    window_history::WindowHistory,
+};
+use crate::Result;
+
+// Resizes the focused window and shrinks/grows the windows next to it that were previously placed
+// by win-move, so that windows snapped side by side stay without gaps between them. The focused
+// window stops growing once a neighbour has shrunk to its minimum size.
+pub fn implement_history_aware_resize_action_on_window(
+    foreground_window: Box<dyn Window>,
+    system: &dyn Desktop,
+    config: &Config,
+    direction: ResizeDirection,
+    step: ResizeStep,
+    history: &mut WindowHistory,
+) -> Result<()> {
+    let monitor = foreground_window.get_current_monitor()?;
+    let monitor_rect = monitor.get_size()?;
+    let gaps = config.get_gaps(system.get_monitor_index(monitor.as_ref(), config.monitor_order)?);
+    let window_rect = foreground_window.get_position()?;
+    let window_margin = foreground_window.get_margin()?;
+    let resized_rect = calculate_resized_window_rect(
+        &monitor_rect,
+        &window_rect,
+        &window_margin,
+        &gaps,
+        direction,
+        step,
+    );
+
+    let all_windows = system.get_all_windows()?;
+    history.prune(&all_windows);
+    let mut neighbours = vec![];
+    for window in all_windows {
+        if window.get_platform_specific_handle() == foreground_window.get_platform_specific_handle()
+            || !history.is_snapped(window.as_ref())
+        {
+            continue;
+        }
+        // A neighbour that can't be read, eg. because it was just closed, is left as it is
+        match get_neighbour_rect(window.as_ref(), monitor.as_ref()) {
+            Ok(Some(neighbour_rect)) => neighbours.push((window, neighbour_rect)),
+            Ok(None) => (),
+            Err(error) => log::warn!(
+                "Couldn't resize neighbour {:?}: {}",
+                window.get_platform_specific_handle(),
+                error
+            ),
+        }
+    }
+
+    let old_visible_rect = get_visible_rect(&window_rect, &window_margin);
+    let new_visible_rect = neighbours.iter().fold(
+        get_visible_rect(&resized_rect, &window_margin),
+        |new_visible_rect, (_, neighbour_rect)| {
+            limit_growth_by_neighbour(
+                &old_visible_rect,
+                &new_visible_rect,
+                neighbour_rect,
+                gaps.inner_gap,
+            )
+        },
+    );
+
+    for (window, neighbour_rect) in neighbours {
+        if let Some(new_neighbour_rect) = calculate_neighbour_rect(
+            &old_visible_rect,
+            &new_visible_rect,
+            &neighbour_rect,
+            gaps.inner_gap,
+        ) {
+            if let Err(error) = resize_neighbour(window.as_ref(), &new_neighbour_rect, history) {
+                log::warn!(
+                    "Couldn't resize neighbour {:?}: {}",
+                    window.get_platform_specific_handle(),
+                    error
+                );
+            }
+        }
+    }
+
+    foreground_window.disable_snapping()?;
+    foreground_window.move_window(&get_window_rect_from_visible(
+        &new_visible_rect,
+        &window_margin,
+    ))?;
+    history.update_position(foreground_window.as_ref())
+}
+
+// Returns the visible rect of a snapped window that may be resized along with the focused window
+fn get_neighbour_rect(window: &dyn Window, monitor: &dyn Monitor) -> Result<Option<Rect>> {
+    if window.get_state()? != WindowState::Normal || !window.get_current_monitor()?.equals(monitor)
+    {
+        return Ok(None);
+    }
+    Ok(Some(get_visible_rect(
+        &window.get_position()?,
+        &window.get_margin()?,
+    )))
+}
+
+fn resize_neighbour(
+    window: &dyn Window,
+    new_neighbour_rect: &Rect,
+    history: &mut WindowHistory,
+) -> Result<()> {
+    log::debug!(
+        "Resizing neighbour {:?} to {:?}",
+        window.get_platform_specific_handle(),
+        new_neighbour_rect
+    );
+    window.move_window(&get_window_rect_from_visible(
+        new_neighbour_rect,
+        &window.get_margin()?,
+    ))?;
+    // Still in its zone, just resized
+    history.update_position(window)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::{
+        action_registry::{run_action, ActionContext},
+        hotkey_action::HotKeyAction,
+    };
+    use crate::fake::desktop::FakeDesktop;
+
+    fn rect(left: i32, right: i32) -> Rect {
+        Rect {
+            left,
+            right,
+            top: 0,
+            bottom: 1040,
+        }
+    }
+
+    // Two windows snapped side by side, the left one focused
+    fn snapped_desktop(history: &mut WindowHistory) -> (FakeDesktop, isize, isize) {
+        let (desktop, left) = FakeDesktop::with_monitor_and_window();
+        let right = desktop.add_window(rect(1000, 1800));
+        for (window, action) in [
+            (right, HotKeyAction::MoveWindowToRightMiddle),
+            (left, HotKeyAction::MoveWindowToLeftMiddle),
+        ] {
+            desktop.set_foreground_window(window);
+            run(&desktop, history, &action);
+        }
+        (desktop, left, right)
+    }
+
+    fn run(desktop: &FakeDesktop, history: &mut WindowHistory, action: &HotKeyAction) {
+        let mut context = ActionContext {
+            system: desktop,
+            config: &Config::default(),
+            history,
+        };
+        run_action(
+            desktop.get_foreground_window().unwrap(),
+            action,
+            &mut context,
+        )
+        .unwrap();
+    }
+
+    fn grow_right() -> HotKeyAction {
+        HotKeyAction::ResizeWindow {
+            direction: ResizeDirection::Right,
+            step: ResizeStep::Pixels(300),
+            history_aware: true,
+        }
+    }
+
+    #[test]
+    fn growing_stops_at_the_minimum_size_of_the_neighbour() {
+        let mut history = WindowHistory::new();
+        let (desktop, left, right) = snapped_desktop(&mut history);
+
+        let mut positions = vec![];
+        for _ in 0..10 {
+            run(&desktop, &mut history, &grow_right());
+            positions.push((desktop.window(left).rect, desktop.window(right).rect));
+        }
+        let (left_rect, right_rect) = positions.last().unwrap().clone();
+        assert_eq!(left_rect, rect(0, 1822));
+        assert_eq!(right_rect, rect(1820, 1920));
+        // Stopped before running out of presses
+        assert_eq!(
+            positions[positions.len() - 2],
+            positions[positions.len() - 1]
+        );
+    }
+
+    #[test]
+    fn window_moved_by_hand_is_not_a_neighbour_anymore() {
+        let mut history = WindowHistory::new();
+        let (desktop, left, right) = snapped_desktop(&mut history);
+        let moved_rect = desktop.window(right).rect;
+        desktop
+            .get_all_windows()
+            .unwrap()
+            .into_iter()
+            .find(|window| window.get_platform_specific_handle() == right)
+            .unwrap()
+            .move_window(&Rect {
+                left: moved_rect.left + 10,
+                ..moved_rect.clone()
+            })
+            .unwrap();
+
+        run(&desktop, &mut history, &grow_right());
+        assert_eq!(desktop.window(left).rect, rect(0, 1261));
+        assert_eq!(desktop.window(right).rect.left, moved_rect.left + 10);
+    }
+}
@@ -0,0 +1,786 @@
+use crate::common::{
+    action::{
+        maximize_window::implement_maximize_action_on_window,
+        minimize_window::implement_minimize_action_on_window,
+        move_window::implement_move_action_on_window,
+        move_window_to_another_screen::{
+            implement_move_action_to_another_screen, implement_move_action_to_monitor,
+        },
+        move_window_to_grid_cell::implement_move_to_grid_cell_action_on_window,
+        move_window_to_zone::implement_move_to_zone_action_on_window,
+        resize_window::implement_resize_action_on_window,
+        resize_window_history_aware::implement_history_aware_resize_action_on_window,
+    },
+    config::Config,
+    enums::ResizeDirection,
+    hotkey_action::HotKeyAction,
+    structs::{GridCell, MonitorTarget, ResizeStep},
+    traits::{Desktop, Window},
+    window_history::WindowHistory,
+};
+use crate::Error;
+use std::time::Instant;
+
+/// State the action handlers have access to
+pub struct ActionContext<'a> {
+    pub system: &'a dyn Desktop,
+    pub config: &'a Config,
+    pub history: &'a mut WindowHistory,
+}
+
+/// Parameter of an action, written next to the action's `type` in the configuration
+pub struct ActionParameter {
+    pub name: &'static str,
+    pub description: &'static str,
+    pub required: bool,
+}
+
+/// Describes an action: the name it's configured with, its parameters and how it's run
+pub struct ActionDescriptor {
+    pub name: &'static str,
+    pub description: &'static str,
+    pub parameters: &'static [ActionParameter],
+    // Builds the action from its parameters
+    parse: fn(toml::Table) -> Result<HotKeyAction, String>,
+    // Parameters of the action, `None` if the action isn't described by this descriptor
+    parameters_of: fn(&HotKeyAction) -> Option<toml::Table>,
+    handler: fn(Box<dyn Window>, &HotKeyAction, &mut ActionContext) -> crate::Result<()>,
+}
+
+impl ActionDescriptor {
+    /// Builds the action from the parameters given in the configuration
+    pub fn parse(&self, parameters: toml::Table) -> Result<HotKeyAction, String> {
+        (self.parse)(parameters).map_err(|error| format!("{}: {}", self.name, error.trim()))
+    }
+
+    /// Returns the parameters of `action` as written in the configuration, `None` if the action
+    /// isn't described by this descriptor
+    pub fn parameters_of(&self, action: &HotKeyAction) -> Option<toml::Table> {
+        (self.parameters_of)(action)
+    }
+
+    pub fn run(
+        &self,
+        foreground_window: Box<dyn Window>,
+        action: &HotKeyAction,
+        context: &mut ActionContext,
+    ) -> crate::Result<()> {
+        (self.handler)(foreground_window, action, context)
+    }
+}
+
+/// Returns the descriptor of the action configured with `name`
+pub fn find_descriptor(name: &str) -> Option<&'static ActionDescriptor> {
+    ACTIONS.iter().find(|descriptor| descriptor.name == name)
+}
+
+/// Returns the descriptor `action` is configured and run with
+pub fn find_descriptor_of(action: &HotKeyAction) -> Option<&'static ActionDescriptor> {
+    ACTIONS
+        .iter()
+        .find(|descriptor| descriptor.parameters_of(action).is_some())
+}
+
+/// Builds an action from its name and the parameters given with it in the configuration
+pub fn parse_action(name: &str, parameters: toml::Table) -> Result<HotKeyAction, String> {
+    find_descriptor(name)
+        .ok_or_else(|| format!("unknown action '{}'", name))?
+        .parse(parameters)
+}
+
+/// Runs the handler of `action` on `foreground_window`
+pub fn run_action(
+    foreground_window: Box<dyn Window>,
+    action: &HotKeyAction,
+    context: &mut ActionContext,
+) -> crate::Result<()> {
+    match find_descriptor_of(action) {
+        Some(descriptor) => descriptor.run(foreground_window, action, context),
+        None => {
+            log::warn!("No handler registered for {:?}", action);
+            Ok(())
+        }
+    }
+}
+
+/// Human readable list of all actions and their parameters
+pub fn format_action_list() -> String {
+    let width = ACTIONS
+        .iter()
+        .map(|descriptor| descriptor.name.len())
+        .max()
+        .unwrap_or_default();
+    let mut list = String::new();
+    for descriptor in ACTIONS {
+        list += &format!(
+            "{:width$}  {}\n",
+            descriptor.name,
+            descriptor.description,
+            width = width
+        );
+        for parameter in descriptor.parameters {
+            list += &format!(
+                "    {:width$}{}{}\n",
+                parameter.name,
+                if parameter.required {
+                    "(required) "
+                } else {
+                    ""
+                },
+                parameter.description,
+                width = width - 2
+            );
+        }
+    }
+    list
+}
+
+fn no_parameters(parameters: toml::Table, action: HotKeyAction) -> Result<HotKeyAction, String> {
+    match parameters.keys().next() {
+        Some(parameter) => Err(format!("unknown parameter '{}'", parameter)),
+        None => Ok(action),
+    }
+}
+
+fn to_table<T: serde::Serialize>(parameters: &T) -> Option<toml::Table> {
+    match toml::Value::try_from(parameters) {
+        Ok(toml::Value::Table(table)) => Some(table),
+        _ => None,
+    }
+}
+
+fn from_table<T: serde::de::DeserializeOwned>(parameters: toml::Table) -> Result<T, String> {
+    toml::Value::Table(parameters)
+        .try_into()
+        .map_err(|error: toml::de::Error| error.message().to_string())
+}
+
+#[derive(serde::Serialize, serde::Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ZoneParameters {
+    zone: String,
+}
+
+#[derive(serde::Serialize, serde::Deserialize)]
+#[serde(deny_unknown_fields)]
+struct MonitorParameters {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    index: Option<usize>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    name: Option<String>,
+}
+
+#[derive(serde::Serialize, serde::Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ResizeParameters {
+    direction: ResizeDirection,
+    #[serde(default)]
+    step: ResizeStep,
+    #[serde(default)]
+    history_aware: bool,
+}
+
+fn move_to_position(
+    foreground_window: Box<dyn Window>,
+    action: &HotKeyAction,
+    context: &mut ActionContext,
+) -> crate::Result<()> {
+    let size_fraction = context.history.next_size_fraction(
+        foreground_window.get_platform_specific_handle(),
+        action,
+        context.config.get_size_cycle_fractions(action),
+        context.config.get_size_cycle_timeout(),
+        Instant::now(),
+    );
+    implement_move_action_on_window(
+        foreground_window.as_ref(),
+        context.system,
+        context.config,
+        action.clone(),
+        size_fraction,
+    )?;
+    context
+        .history
+        .record(foreground_window.as_ref(), action.clone())
+}
+
+fn move_to_grid_cell(
+    foreground_window: Box<dyn Window>,
+    action: &HotKeyAction,
+    cell: &GridCell,
+    context: &mut ActionContext,
+) -> crate::Result<()> {
+    implement_move_to_grid_cell_action_on_window(
+        foreground_window.as_ref(),
+        context.system,
+        cell,
+        context.config,
+    )?;
+    context
+        .history
+        .record(foreground_window.as_ref(), action.clone())
+}
+
+fn move_to_zone(
+    foreground_window: Box<dyn Window>,
+    action: &HotKeyAction,
+    zone: &str,
+    context: &mut ActionContext,
+) -> crate::Result<()> {
+    implement_move_to_zone_action_on_window(
+        foreground_window.as_ref(),
+        context.system,
+        zone,
+        context.config,
+    )?;
+    context
+        .history
+        .record(foreground_window.as_ref(), action.clone())
+}
+
+fn move_to_another_screen(
+    foreground_window: Box<dyn Window>,
+    action: &HotKeyAction,
+    context: &mut ActionContext,
+) -> crate::Result<()> {
+    implement_move_action_to_another_screen(
+        foreground_window,
+        context.system,
+        context.config,
+        action.clone(),
+    )
+}
+
+fn move_to_monitor(
+    foreground_window: Box<dyn Window>,
+    _action: &HotKeyAction,
+    target: &MonitorTarget,
+    context: &mut ActionContext,
+) -> crate::Result<()> {
+    implement_move_action_to_monitor(foreground_window, context.system, context.config, target)
+}
+
+fn resize(
+    foreground_window: Box<dyn Window>,
+    _action: &HotKeyAction,
+    direction: &ResizeDirection,
+    step: &ResizeStep,
+    history_aware: &bool,
+    context: &mut ActionContext,
+) -> crate::Result<()> {
+    if *history_aware {
+        implement_history_aware_resize_action_on_window(
+            foreground_window,
+            context.system,
+            context.config,
+            *direction,
+            *step,
+            context.history,
+        )
+    } else {
+        implement_resize_action_on_window(
+            foreground_window,
+            context.system,
+            context.config,
+            *direction,
+            *step,
+        )
+    }
+}
+
+// Handler of an action with parameters. Calls `$handler` with the fields bound by `$pattern`,
+// actions of other variants are reported as an error instead.
+macro_rules! variant_handler {
+    ($pattern:pat => $handler:ident($($field:ident),*)) => {
+        |foreground_window, action, context| match action {
+            $pattern => $handler(foreground_window, action, $($field,)* context),
+            _ => Err(Error::UnhandledAction {
+                handler: stringify!($handler),
+                action: format!("{:?}", action),
+            }),
+        }
+    };
+}
+
+// Action without parameters, configured with the name of its `HotKeyAction` variant
+macro_rules! simple_action {
+    ($variant:ident, $description:expr, $handler:expr) => {
+        ActionDescriptor {
+            name: stringify!($variant),
+            description: $description,
+            parameters: &[],
+            parse: |parameters| no_parameters(parameters, HotKeyAction::$variant),
+            parameters_of: |action| matches!(action, HotKeyAction::$variant).then(toml::Table::new),
+            handler: $handler,
+        }
+    };
+}
+
+// Resize actions kept from before resizing took parameters, resizing by the default step
+macro_rules! legacy_resize_action {
+    ($name:ident, $direction:ident, $history_aware:expr, $description:expr) => {
+        ActionDescriptor {
+            name: stringify!($name),
+            description: $description,
+            parameters: &[],
+            parse: |parameters| {
+                no_parameters(
+                    parameters,
+                    HotKeyAction::ResizeWindow {
+                        direction: ResizeDirection::$direction,
+                        step: ResizeStep::default(),
+                        history_aware: $history_aware,
+                    },
+                )
+            },
+            parameters_of: |action| {
+                (*action
+                    == HotKeyAction::ResizeWindow {
+                        direction: ResizeDirection::$direction,
+                        step: ResizeStep::default(),
+                        history_aware: $history_aware,
+                    })
+                .then(toml::Table::new)
+            },
+            handler: variant_handler!(
+                HotKeyAction::ResizeWindow {
+                    direction,
+                    step,
+                    history_aware,
+                } => resize(direction, step, history_aware)
+            ),
+        }
+    };
+}
+
+/// All actions, in the order they're listed
+pub static ACTIONS: &[ActionDescriptor] = &[
+    simple_action!(
+        MoveWindowToLeftBottom,
+        "Moves the window to the bottom left corner",
+        move_to_position
+    ),
+    simple_action!(
+        MoveWindowToBottom,
+        "Moves the window to the bottom half",
+        move_to_position
+    ),
+    simple_action!(
+        MoveWindowToRightBottom,
+        "Moves the window to the bottom right corner",
+        move_to_position
+    ),
+    simple_action!(
+        MoveWindowToLeftMiddle,
+        "Moves the window to the left half",
+        move_to_position
+    ),
+    simple_action!(
+        MoveWindowToRightMiddle,
+        "Moves the window to the right half",
+        move_to_position
+    ),
+    simple_action!(
+        MoveWindowToLeftTop,
+        "Moves the window to the top left corner",
+        move_to_position
+    ),
+    simple_action!(
+        MoveWindowToTop,
+        "Moves the window to the top half",
+        move_to_position
+    ),
+    simple_action!(
+        MoveWindowToRightTop,
+        "Moves the window to the top right corner",
+        move_to_position
+    ),
+    ActionDescriptor {
+        name: "MoveToGridCell",
+        description: "Moves the window to a cell or a span of cells of the monitor's grid",
+        parameters: &[
+            ActionParameter {
+                name: "column",
+                description: "Column of the cell, starting from 0",
+                required: true,
+            },
+            ActionParameter {
+                name: "row",
+                description: "Row of the cell, starting from 0",
+                required: true,
+            },
+            ActionParameter {
+                name: "column_span",
+                description: "Number of columns covered, default 1",
+                required: false,
+            },
+            ActionParameter {
+                name: "row_span",
+                description: "Number of rows covered, default 1",
+                required: false,
+            },
+        ],
+        parse: |parameters| from_table(parameters).map(HotKeyAction::MoveWindowToGridCell),
+        parameters_of: |action| match action {
+            HotKeyAction::MoveWindowToGridCell(cell) => to_table(cell),
+            _ => None,
+        },
+        handler: variant_handler!(
+            HotKeyAction::MoveWindowToGridCell(cell) => move_to_grid_cell(cell)
+        ),
+    },
+    ActionDescriptor {
+        name: "MoveToZone",
+        description: "Moves the window to a zone defined in [[zones]]",
+        parameters: &[ActionParameter {
+            name: "zone",
+            description: "Name of the zone",
+            required: true,
+        }],
+        parse: |parameters| {
+            from_table(parameters).map(|parameters: ZoneParameters| {
+                HotKeyAction::MoveWindowToZone {
+                    zone: parameters.zone,
+                }
+            })
+        },
+        parameters_of: |action| match action {
+            HotKeyAction::MoveWindowToZone { zone } => {
+                to_table(&ZoneParameters { zone: zone.clone() })
+            }
+            _ => None,
+        },
+        handler: variant_handler!(
+            HotKeyAction::MoveWindowToZone { zone } => move_to_zone(zone)
+        ),
+    },
+    simple_action!(
+        MinimizeWindow,
+        "Minimizes / restores the window",
+        |window, _, _| implement_minimize_action_on_window(window)
+    ),
+    simple_action!(
+        MaximizeWindow,
+        "Maximizes / restores the window",
+        |window, _, _| implement_maximize_action_on_window(window)
+    ),
+    simple_action!(
+        MoveWindowToLeftScreen,
+        "Moves the window to the monitor on the left, stops at the last monitor",
+        move_to_another_screen
+    ),
+    simple_action!(
+        MoveWindowToRightScreen,
+        "Moves the window to the monitor on the right, stops at the last monitor",
+        move_to_another_screen
+    ),
+    simple_action!(
+        MoveWindowToLeftScreenContinuous,
+        "Moves the window to the monitor on the left, wraps around",
+        move_to_another_screen
+    ),
+    simple_action!(
+        MoveWindowToRightScreenContinuous,
+        "Moves the window to the monitor on the right, wraps around",
+        move_to_another_screen
+    ),
+    simple_action!(
+        MoveWindowToUpperScreen,
+        "Moves the window to the monitor above",
+        move_to_another_screen
+    ),
+    simple_action!(
+        MoveWindowToLowerScreen,
+        "Moves the window to the monitor below",
+        move_to_another_screen
+    ),
+    ActionDescriptor {
+        name: "MoveToMonitor",
+        description: "Moves the window to the given monitor",
+        parameters: &[
+            ActionParameter {
+                name: "index",
+                description: "Index of the monitor in the monitor order, starting from 0",
+                required: false,
+            },
+            ActionParameter {
+                name: "name",
+                description: "Name given to the monitor in [[monitors]], instead of 'index'",
+                required: false,
+            },
+        ],
+        parse: |parameters| match from_table(parameters)? {
+            MonitorParameters {
+                index: Some(index),
+                name: None,
+            } => Ok(HotKeyAction::MoveWindowToMonitor(MonitorTarget::Index(
+                index,
+            ))),
+            MonitorParameters {
+                index: None,
+                name: Some(name),
+            } => Ok(HotKeyAction::MoveWindowToMonitor(MonitorTarget::Name(name))),
+            _ => Err("takes either 'index' or 'name'".to_string()),
+        },
+        parameters_of: |action| match action {
+            HotKeyAction::MoveWindowToMonitor(MonitorTarget::Index(index)) => {
+                to_table(&MonitorParameters {
+                    index: Some(*index),
+                    name: None,
+                })
+            }
+            HotKeyAction::MoveWindowToMonitor(MonitorTarget::Name(name)) => {
+                to_table(&MonitorParameters {
+                    index: None,
+                    name: Some(name.clone()),
+                })
+            }
+            _ => None,
+        },
+        handler: variant_handler!(
+            HotKeyAction::MoveWindowToMonitor(target) => move_to_monitor(target)
+        ),
+    },
+    legacy_resize_action!(
+        ChangeWindowSizeTowardsLeftBottom,
+        LeftBottom,
+        false,
+        "Resizes the window towards the bottom left corner"
+    ),
+    legacy_resize_action!(
+        ChangeWindowSizeTowardsBottom,
+        Bottom,
+        false,
+        "Resizes the window towards the bottom edge"
+    ),
+    legacy_resize_action!(
+        ChangeWindowSizeTowardsRightBottom,
+        RightBottom,
+        false,
+        "Resizes the window towards the bottom right corner"
+    ),
+    legacy_resize_action!(
+        ChangeWindowSizeTowardsLeftMiddle,
+        Left,
+        false,
+        "Resizes the window towards the left edge"
+    ),
+    legacy_resize_action!(
+        ChangeWindowSizeTowardsRightMiddle,
+        Right,
+        false,
+        "Resizes the window towards the right edge"
+    ),
+    legacy_resize_action!(
+        ChangeWindowSizeTowardsLeftTop,
+        LeftTop,
+        false,
+        "Resizes the window towards the top left corner"
+    ),
+    legacy_resize_action!(
+        ChangeWindowSizeTowardsTop,
+        Top,
+        false,
+        "Resizes the window towards the top edge"
+    ),
+    legacy_resize_action!(
+        ChangeWindowSizeTowardsRightTop,
+        RightTop,
+        false,
+        "Resizes the window towards the top right corner"
+    ),
+    legacy_resize_action!(
+        ChangeWindowSizeTowardsLeftBottomHistoryAware,
+        LeftBottom,
+        true,
+        "Resizes the window and its neighbours towards the bottom left corner"
+    ),
+    legacy_resize_action!(
+        ChangeWindowSizeTowardsBottomHistoryAware,
+        Bottom,
+        true,
+        "Resizes the window and its neighbours towards the bottom edge"
+    ),
+    legacy_resize_action!(
+        ChangeWindowSizeTowardsRightBottomHistoryAware,
+        RightBottom,
+        true,
+        "Resizes the window and its neighbours towards the bottom right corner"
+    ),
+    legacy_resize_action!(
+        ChangeWindowSizeTowardsLeftMiddleHistoryAware,
+        Left,
+        true,
+        "Resizes the window and its neighbours towards the left edge"
+    ),
+    legacy_resize_action!(
+        ChangeWindowSizeTowardsRightMiddleHistoryAware,
+        Right,
+        true,
+        "Resizes the window and its neighbours towards the right edge"
+    ),
+    legacy_resize_action!(
+        ChangeWindowSizeTowardsLeftTopHistoryAware,
+        LeftTop,
+        true,
+        "Resizes the window and its neighbours towards the top left corner"
+    ),
+    legacy_resize_action!(
+        ChangeWindowSizeTowardsTopHistoryAware,
+        Top,
+        true,
+        "Resizes the window and its neighbours towards the top edge"
+    ),
+    legacy_resize_action!(
+        ChangeWindowSizeTowardsRightTopHistoryAware,
+        RightTop,
+        true,
+        "Resizes the window and its neighbours towards the top right corner"
+    ),
+    ActionDescriptor {
+        name: "Resize",
+        description: "Resizes the window towards an edge or a corner of the monitor",
+        parameters: &[
+            ActionParameter {
+                name: "direction",
+                description:
+                    "Left, Right, Top, Bottom, LeftTop, RightTop, LeftBottom or RightBottom",
+                required: true,
+            },
+            ActionParameter {
+                name: "step",
+                description: "Pixels or a percentage of the monitor, default \"10%\"",
+                required: false,
+            },
+            ActionParameter {
+                name: "history_aware",
+                description: "Resizes the windows next to it as well, default false",
+                required: false,
+            },
+        ],
+        parse: |parameters| {
+            from_table(parameters).map(|parameters: ResizeParameters| HotKeyAction::ResizeWindow {
+                direction: parameters.direction,
+                step: parameters.step,
+                history_aware: parameters.history_aware,
+            })
+        },
+        parameters_of: |action| match action {
+            HotKeyAction::ResizeWindow {
+                direction,
+                step,
+                history_aware,
+            } => to_table(&ResizeParameters {
+                direction: *direction,
+                step: *step,
+                history_aware: *history_aware,
+            }),
+            _ => None,
+        },
+        handler: variant_handler!(
+            HotKeyAction::ResizeWindow {
+                direction,
+                step,
+                history_aware,
+            } => resize(direction, step, history_aware)
+        ),
+    },
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fake::desktop::FakeDesktop;
+
+    fn table(parameters: &str) -> toml::Table {
+        parameters.parse().unwrap()
+    }
+
+    #[test]
+    fn action_names_are_unique() {
+        for (index, descriptor) in ACTIONS.iter().enumerate() {
+            assert!(
+                ACTIONS[index + 1..]
+                    .iter()
+                    .all(|other| other.name != descriptor.name),
+                "{} registered twice",
+                descriptor.name
+            );
+        }
+    }
+
+    #[test]
+    fn every_action_parses_back_from_its_parameters() {
+        let actions = [
+            HotKeyAction::MoveWindowToLeftBottom,
+            HotKeyAction::MoveWindowToGridCell(GridCell {
+                column: 2,
+                row: 0,
+                column_span: 1,
+                row_span: 2,
+            }),
+            HotKeyAction::MoveWindowToZone {
+                zone: "main".to_string(),
+            },
+            HotKeyAction::MaximizeWindow,
+            HotKeyAction::MoveWindowToLowerScreen,
+            HotKeyAction::MoveWindowToMonitor(MonitorTarget::Name("left".to_string())),
+            HotKeyAction::ResizeWindow {
+                direction: ResizeDirection::Top,
+                step: ResizeStep::default(),
+                history_aware: true,
+            },
+            HotKeyAction::ResizeWindow {
+                direction: ResizeDirection::Left,
+                step: ResizeStep::Pixels(20),
+                history_aware: false,
+            },
+        ];
+        for action in actions {
+            let descriptor = find_descriptor_of(&action).unwrap();
+            let parameters = descriptor.parameters_of(&action).unwrap();
+            assert_eq!(descriptor.parse(parameters), Ok(action));
+        }
+    }
+
+    #[test]
+    fn default_resize_uses_legacy_name() {
+        let action = parse_action("Resize", table("direction = \"Left\"")).unwrap();
+        assert_eq!(
+            find_descriptor_of(&action).unwrap().name,
+            "ChangeWindowSizeTowardsLeftMiddle"
+        );
+    }
+
+    #[test]
+    fn invalid_parameters_are_rejected() {
+        assert!(parse_action("MaximizeWindow", table("zone = \"main\"")).is_err());
+        assert!(parse_action("MoveToZone", toml::Table::new()).is_err());
+        assert!(parse_action("MoveToMonitor", table("index = 1\nname = \"left\"")).is_err());
+        assert!(parse_action("Teleport", toml::Table::new()).is_err());
+    }
+
+    #[test]
+    fn handler_of_another_action_returns_an_error() {
+        let (desktop, _) = FakeDesktop::with_monitor_and_window();
+        let config = Config::default();
+        let mut history = WindowHistory::new();
+        let mut context = ActionContext {
+            system: &desktop,
+            config: &config,
+            history: &mut history,
+        };
+        let result = find_descriptor("MoveToZone").unwrap().run(
+            desktop.get_foreground_window().unwrap(),
+            &HotKeyAction::MaximizeWindow,
+            &mut context,
+        );
+        assert_eq!(
+            result,
+            Err(Error::UnhandledAction {
+                handler: "move_to_zone",
+                action: "MaximizeWindow".to_string(),
+            })
+        );
+    }
+}
@@ -0,0 +1,572 @@
+use crate::common::{
+    enums::ResizeDirection,
+    structs::{Gaps, Rect, ResizeStep, WindowBorderSize},
+};
+
+enum HorizontalDirection {
+    Left,
+    Right,
+    None,
+}
+
+enum VerticalDirection {
+    Top,
+    Bottom,
+    None,
+}
+
+fn get_resize_directions(direction: ResizeDirection) -> (HorizontalDirection, VerticalDirection) {
+    match direction {
+        ResizeDirection::LeftBottom => (HorizontalDirection::Left, VerticalDirection::Bottom),
+        ResizeDirection::Bottom => (HorizontalDirection::None, VerticalDirection::Bottom),
+        ResizeDirection::RightBottom => (HorizontalDirection::Right, VerticalDirection::Bottom),
+        ResizeDirection::Left => (HorizontalDirection::Left, VerticalDirection::None),
+        ResizeDirection::Right => (HorizontalDirection::Right, VerticalDirection::None),
+        ResizeDirection::LeftTop => (HorizontalDirection::Left, VerticalDirection::Top),
+        ResizeDirection::Top => (HorizontalDirection::None, VerticalDirection::Top),
+        ResizeDirection::RightTop => (HorizontalDirection::Right, VerticalDirection::Top),
+    }
+}
+
+// Grows the edge `near` towards `limit` by `step`. If the edge already reached the limit,
+// the opposite edge `far` is moved towards it instead, keeping at least `step` between them.
+// Returns the new (near, far) pair.
+fn grow_or_shrink(near: i32, far: i32, limit: i32, step: i32) -> (i32, i32) {
+    let direction = (limit - far).signum();
+    if (limit - near) * direction > 0 {
+        let grown = near + direction * step;
+        let clamped = if direction < 0 {
+            grown.max(limit)
+        } else {
+            grown.min(limit)
+        };
+        (clamped, far)
+    } else {
+        let shrunk = far + direction * step;
+        let clamped = if direction < 0 {
+            shrunk.max(near - direction * step)
+        } else {
+            shrunk.min(near - direction * step)
+        };
+        (near, clamped)
+    }
+}
+
+// Tolerance used when deciding whether two windows are next to each other, on top of the inner
+// gap. Windows placed by `calculate_window_rect` overlap by a pixel or two due to the border
+// compensation.
+const ADJACENCY_TOLERANCE: i32 = 4;
+
+// Smallest visible width or height a neighbour is shrunk to when the window next to it grows
+const MIN_NEIGHBOUR_SIZE: i32 = 100;
+
+/// Returns the visible part of a window, ie. the window rect without the extended frame
+pub fn get_visible_rect(window_rect: &Rect, window_margin: &WindowBorderSize) -> Rect {
+    Rect {
+        left: window_rect.left - window_margin.left,
+        right: window_rect.right - window_margin.right,
+        top: window_rect.top - window_margin.top,
+        bottom: window_rect.bottom - window_margin.bottom,
+    }
+}
+
+/// Inverse of `get_visible_rect`, adds the extended frame back to the visible rect
+pub fn get_window_rect_from_visible(visible_rect: &Rect, window_margin: &WindowBorderSize) -> Rect {
+    Rect {
+        left: visible_rect.left + window_margin.left,
+        right: visible_rect.right + window_margin.right,
+        top: visible_rect.top + window_margin.top,
+        bottom: visible_rect.bottom + window_margin.bottom,
+    }
+}
+
+/// Calculates the new rect of a window resized towards the edge or corner given by `direction`.
+///
+/// The window grows towards the given edge by `step`, relative to the monitor's work area. Once the
+/// visible part of the window touches the edge of the monitor, the window shrinks from the
+/// opposite side instead. The monitor's edge is moved inwards by the outer padding of `gaps`.
+pub fn calculate_resized_window_rect(
+    monitor_rect: &Rect,
+    window_rect: &Rect,
+    window_margin: &WindowBorderSize,
+    gaps: &Gaps,
+    direction: ResizeDirection,
+    step: ResizeStep,
+) -> Rect {
+    let step_x = step.to_pixels(monitor_rect.width());
+    let step_y = step.to_pixels(monitor_rect.height());
+    let outer_padding = gaps.outer_padding.max(0);
+    let monitor_rect = Rect {
+        left: monitor_rect.left + outer_padding,
+        right: monitor_rect.right - outer_padding,
+        top: monitor_rect.top + outer_padding,
+        bottom: monitor_rect.bottom - outer_padding,
+    };
+
+    // Calculate on the visible part of the window, the margins are added back at the end
+    let Rect {
+        mut left,
+        mut right,
+        mut top,
+        mut bottom,
+    } = get_visible_rect(window_rect, window_margin);
+
+    let (horizontal, vertical) = get_resize_directions(direction);
+
+    match horizontal {
+        HorizontalDirection::Left => {
+            (left, right) = grow_or_shrink(left, right, monitor_rect.left, step_x);
+        }
+        HorizontalDirection::Right => {
+            (right, left) = grow_or_shrink(right, left, monitor_rect.right, step_x);
+        }
+        HorizontalDirection::None => (),
+    }
+
+    match vertical {
+        VerticalDirection::Top => {
+            (top, bottom) = grow_or_shrink(top, bottom, monitor_rect.top, step_y);
+        }
+        VerticalDirection::Bottom => {
+            (bottom, top) = grow_or_shrink(bottom, top, monitor_rect.bottom, step_y);
+        }
+        VerticalDirection::None => (),
+    }
+
+    get_window_rect_from_visible(
+        &Rect {
+            left,
+            right,
+            top,
+            bottom,
+        },
+        window_margin,
+    )
+}
+
+fn overlaps(a_start: i32, a_end: i32, b_start: i32, b_end: i32) -> bool {
+    a_start + ADJACENCY_TOLERANCE < b_end && b_start + ADJACENCY_TOLERANCE < a_end
+}
+
+fn is_touching(edge: i32, other_edge: i32, inner_gap: i32) -> bool {
+    (edge - other_edge).abs() <= inner_gap + ADJACENCY_TOLERANCE
+}
+
+/// Limits the growth of a resized window so that the neighbour touching it keeps at least
+/// `MIN_NEIGHBOUR_SIZE`, or its current size if it's already smaller. All rects are visible rects.
+/// Returns the limited `new_rect`.
+pub fn limit_growth_by_neighbour(
+    old_rect: &Rect,
+    new_rect: &Rect,
+    neighbour_rect: &Rect,
+    inner_gap: i32,
+) -> Rect {
+    let inner_gap = inner_gap.max(0);
+    let mut limited = new_rect.clone();
+    let vertical_overlap = overlaps(
+        old_rect.top,
+        old_rect.bottom,
+        neighbour_rect.top,
+        neighbour_rect.bottom,
+    );
+    let horizontal_overlap = overlaps(
+        old_rect.left,
+        old_rect.right,
+        neighbour_rect.left,
+        neighbour_rect.right,
+    );
+    let spare_width = (neighbour_rect.width() - MIN_NEIGHBOUR_SIZE).max(0);
+    let spare_height = (neighbour_rect.height() - MIN_NEIGHBOUR_SIZE).max(0);
+
+    if vertical_overlap && is_touching(old_rect.right, neighbour_rect.left, inner_gap) {
+        limited.right = limited.right.min(old_rect.right + spare_width);
+    }
+    if vertical_overlap && is_touching(old_rect.left, neighbour_rect.right, inner_gap) {
+        limited.left = limited.left.max(old_rect.left - spare_width);
+    }
+    if horizontal_overlap && is_touching(old_rect.bottom, neighbour_rect.top, inner_gap) {
+        limited.bottom = limited.bottom.min(old_rect.bottom + spare_height);
+    }
+    if horizontal_overlap && is_touching(old_rect.top, neighbour_rect.bottom, inner_gap) {
+        limited.top = limited.top.max(old_rect.top - spare_height);
+    }
+    limited
+}
+
+/// Calculates the new visible rect of a window next to a resized window.
+///
+/// Every edge of the resized window that moved drags along the opposite edge of the neighbour
+/// touching it, so that no gap or overlap appears between them. Edges `inner_gap` apart count as
+/// touching. The neighbour isn't shrunk below `MIN_NEIGHBOUR_SIZE`, limit the resized window with
+/// `limit_growth_by_neighbour` first to keep them apart. All rects are visible rects. Returns
+/// `None` if the neighbour isn't affected by the resize.
+pub fn calculate_neighbour_rect(
+    old_rect: &Rect,
+    new_rect: &Rect,
+    neighbour_rect: &Rect,
+    inner_gap: i32,
+) -> Option<Rect> {
+    let inner_gap = inner_gap.max(0);
+    let mut target = neighbour_rect.clone();
+    let vertical_overlap = overlaps(
+        old_rect.top,
+        old_rect.bottom,
+        neighbour_rect.top,
+        neighbour_rect.bottom,
+    );
+    let horizontal_overlap = overlaps(
+        old_rect.left,
+        old_rect.right,
+        neighbour_rect.left,
+        neighbour_rect.right,
+    );
+
+    if vertical_overlap && is_touching(old_rect.right, neighbour_rect.left, inner_gap) {
+        target.left += new_rect.right - old_rect.right;
+    }
+    if vertical_overlap && is_touching(old_rect.left, neighbour_rect.right, inner_gap) {
+        target.right += new_rect.left - old_rect.left;
+    }
+    if horizontal_overlap && is_touching(old_rect.bottom, neighbour_rect.top, inner_gap) {
+        target.top += new_rect.bottom - old_rect.bottom;
+    }
+    if horizontal_overlap && is_touching(old_rect.top, neighbour_rect.bottom, inner_gap) {
+        target.bottom += new_rect.top - old_rect.top;
+    }
+
+    // Edges that moved inwards stop at the minimum size, unless the neighbour was already smaller
+    let min_width = MIN_NEIGHBOUR_SIZE.min(neighbour_rect.width());
+    let min_height = MIN_NEIGHBOUR_SIZE.min(neighbour_rect.height());
+    target.left = target.left.min(target.right - min_width);
+    target.right = target.right.max(target.left + min_width);
+    target.top = target.top.min(target.bottom - min_height);
+    target.bottom = target.bottom.max(target.top + min_height);
+
+    if target == *neighbour_rect {
+        None
+    } else {
+        Some(target)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn monitor() -> Rect {
+        Rect {
+            left: 0,
+            right: 1920,
+            top: 0,
+            bottom: 1040,
+        }
+    }
+
+    fn no_margin() -> WindowBorderSize {
+        WindowBorderSize {
+            left: 0,
+            right: 0,
+            top: 0,
+            bottom: 0,
+        }
+    }
+
+    #[test]
+    fn grows_towards_edge() {
+        let window = Rect {
+            left: 960,
+            right: 1920,
+            top: 0,
+            bottom: 1040,
+        };
+        assert_eq!(
+            calculate_resized_window_rect(
+                &monitor(),
+                &window,
+                &no_margin(),
+                &Gaps::default(),
+                ResizeDirection::Left,
+                ResizeStep::default()
+            ),
+            Rect {
+                left: 768,
+                right: 1920,
+                top: 0,
+                bottom: 1040,
+            }
+        );
+    }
+
+    #[test]
+    fn growing_stops_at_monitor_edge() {
+        let window = Rect {
+            left: 100,
+            right: 960,
+            top: 0,
+            bottom: 1040,
+        };
+        assert_eq!(
+            calculate_resized_window_rect(
+                &monitor(),
+                &window,
+                &no_margin(),
+                &Gaps::default(),
+                ResizeDirection::Left,
+                ResizeStep::default()
+            ),
+            Rect {
+                left: 0,
+                right: 960,
+                top: 0,
+                bottom: 1040,
+            }
+        );
+    }
+
+    #[test]
+    fn shrinks_from_opposite_side_at_monitor_edge() {
+        let window = Rect {
+            left: 0,
+            right: 960,
+            top: 0,
+            bottom: 1040,
+        };
+        assert_eq!(
+            calculate_resized_window_rect(
+                &monitor(),
+                &window,
+                &no_margin(),
+                &Gaps::default(),
+                ResizeDirection::Left,
+                ResizeStep::default()
+            ),
+            Rect {
+                left: 0,
+                right: 768,
+                top: 0,
+                bottom: 1040,
+            }
+        );
+        assert_eq!(
+            calculate_resized_window_rect(
+                &monitor(),
+                &window,
+                &no_margin(),
+                &Gaps::default(),
+                ResizeDirection::Bottom,
+                ResizeStep::default()
+            ),
+            Rect {
+                left: 0,
+                right: 960,
+                top: 104,
+                bottom: 1040,
+            }
+        );
+    }
+
+    #[test]
+    fn grows_by_pixel_step() {
+        let window = Rect {
+            left: 960,
+            right: 1920,
+            top: 0,
+            bottom: 1040,
+        };
+        assert_eq!(
+            calculate_resized_window_rect(
+                &monitor(),
+                &window,
+                &no_margin(),
+                &Gaps::default(),
+                ResizeDirection::Left,
+                ResizeStep::Pixels(50)
+            ),
+            Rect {
+                left: 910,
+                right: 1920,
+                top: 0,
+                bottom: 1040,
+            }
+        );
+    }
+
+    #[test]
+    fn shrinking_keeps_one_step() {
+        let window = Rect {
+            left: 1700,
+            right: 1920,
+            top: 0,
+            bottom: 1040,
+        };
+        assert_eq!(
+            calculate_resized_window_rect(
+                &monitor(),
+                &window,
+                &no_margin(),
+                &Gaps::default(),
+                ResizeDirection::Right,
+                ResizeStep::default()
+            ),
+            Rect {
+                left: 1728,
+                right: 1920,
+                top: 0,
+                bottom: 1040,
+            }
+        );
+    }
+
+    #[test]
+    fn corner_resize_takes_margin_into_account() {
+        let border = WindowBorderSize {
+            left: -7,
+            right: 7,
+            top: 0,
+            bottom: 7,
+        };
+        let window = Rect {
+            left: 953,
+            right: 1927,
+            top: 520,
+            bottom: 1047,
+        };
+        assert_eq!(
+            calculate_resized_window_rect(
+                &monitor(),
+                &window,
+                &border,
+                &Gaps::default(),
+                ResizeDirection::RightTop,
+                ResizeStep::default()
+            ),
+            Rect {
+                left: 1145,
+                right: 1927,
+                top: 416,
+                bottom: 1047,
+            }
+        );
+    }
+
+    #[test]
+    fn growing_stops_at_outer_padding() {
+        let window = Rect {
+            left: 100,
+            right: 960,
+            top: 0,
+            bottom: 1040,
+        };
+        assert_eq!(
+            calculate_resized_window_rect(
+                &monitor(),
+                &window,
+                &no_margin(),
+                &Gaps {
+                    inner_gap: 0,
+                    outer_padding: 10,
+                },
+                ResizeDirection::Left,
+                ResizeStep::default()
+            ),
+            Rect {
+                left: 10,
+                right: 960,
+                top: 0,
+                bottom: 1040,
+            }
+        );
+    }
+
+    #[test]
+    fn neighbour_follows_resized_edge() {
+        let old_rect = Rect {
+            left: 0,
+            right: 961,
+            top: 0,
+            bottom: 1040,
+        };
+        let new_rect = Rect {
+            left: 0,
+            right: 1153,
+            top: 0,
+            bottom: 1040,
+        };
+        let neighbour = Rect {
+            left: 959,
+            right: 1920,
+            top: 0,
+            bottom: 1040,
+        };
+        assert_eq!(
+            calculate_neighbour_rect(&old_rect, &new_rect, &neighbour, 0),
+            Some(Rect {
+                left: 1151,
+                right: 1920,
+                top: 0,
+                bottom: 1040,
+            })
+        );
+    }
+
+    #[test]
+    fn unrelated_window_is_not_affected() {
+        let old_rect = Rect {
+            left: 0,
+            right: 961,
+            top: 0,
+            bottom: 520,
+        };
+        let new_rect = Rect {
+            left: 0,
+            right: 961,
+            top: 0,
+            bottom: 624,
+        };
+        let neighbour = Rect {
+            left: 959,
+            right: 1920,
+            top: 520,
+            bottom: 1040,
+        };
+        assert_eq!(
+            calculate_neighbour_rect(&old_rect, &new_rect, &neighbour, 0),
+            None
+        );
+    }
+
+    #[test]
+    fn neighbour_across_inner_gap_follows_resized_edge() {
+        let old_rect = Rect {
+            left: 0,
+            right: 955,
+            top: 0,
+            bottom: 1040,
+        };
+        let new_rect = Rect {
+            left: 0,
+            right: 1147,
+            top: 0,
+            bottom: 1040,
+        };
+        let neighbour = Rect {
+            left: 965,
+            right: 1920,
+            top: 0,
+            bottom: 1040,
+        };
+        assert_eq!(
+            calculate_neighbour_rect(&old_rect, &new_rect, &neighbour, 10),
+            Some(Rect {
+                left: 1157,
+                right: 1920,
+                top: 0,
+                bottom: 1040,
+            })
+        );
+        assert_eq!(
+            calculate_neighbour_rect(&old_rect, &new_rect, &neighbour, 0),
+            None
+        );
+    }
+}
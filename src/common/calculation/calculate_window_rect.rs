@@ -1,6 +1,6 @@
 use crate::common::{
     hotkey_action::HotKeyAction,
-    structs::{Rect, WindowBorderSize, WindowPosition},
+    structs::{Gaps, GridCell, GridLayout, Rect, WindowBorderSize, Zone, ZoneValue},
 };
 
 // 1px horizontal border seems to happen even when taking extended frame into account,
@@ -12,46 +12,261 @@ use crate::common::{
 pub fn calculate_window_rect(
     monitor_rect: &Rect,
     window_margin: &WindowBorderSize,
+    gaps: &Gaps,
     action: HotKeyAction,
 ) -> Rect {
-    let left = match action {
+    let half_width = monitor_rect.width() / 2;
+    let half_height = monitor_rect.height() / 2;
+
+    let (left, right) = match action {
+        HotKeyAction::MoveWindowToBottom | HotKeyAction::MoveWindowToTop => {
+            (0, monitor_rect.width())
+        }
         HotKeyAction::MoveWindowToRightBottom
         | HotKeyAction::MoveWindowToRightMiddle
-        | HotKeyAction::MoveWindowToRightTop => (monitor_rect.width() / 2) - 1,
-        _ => 0,
-    } + monitor_rect.left;
+        | HotKeyAction::MoveWindowToRightTop => (half_width, half_width * 2),
+        _ => (0, half_width),
+    };
 
-    let top = match action {
+    let (top, bottom) = match action {
+        HotKeyAction::MoveWindowToLeftMiddle | HotKeyAction::MoveWindowToRightMiddle => {
+            (0, monitor_rect.height())
+        }
         HotKeyAction::MoveWindowToLeftBottom
         | HotKeyAction::MoveWindowToBottom
-        | HotKeyAction::MoveWindowToRightBottom => monitor_rect.height() / 2,
-        _ => 0,
-    } + monitor_rect.top;
+        | HotKeyAction::MoveWindowToRightBottom => (half_height, half_height * 2),
+        _ => (0, half_height),
+    };
+
+    place_window_into_zone(
+        monitor_rect,
+        &offset_zone(monitor_rect, left, right, top, bottom),
+        window_margin,
+        gaps,
+    )
+}
+
+// Tolerance for considering a window to be in a zone, the window manager may round positions
+const ZONE_MATCH_TOLERANCE: i32 = 2;
+
+const MOVE_ACTIONS: [HotKeyAction; 8] = [
+    HotKeyAction::MoveWindowToLeftBottom,
+    HotKeyAction::MoveWindowToBottom,
+    HotKeyAction::MoveWindowToRightBottom,
+    HotKeyAction::MoveWindowToLeftMiddle,
+    HotKeyAction::MoveWindowToRightMiddle,
+    HotKeyAction::MoveWindowToLeftTop,
+    HotKeyAction::MoveWindowToTop,
+    HotKeyAction::MoveWindowToRightTop,
+];
+
+/// Returns the move action whose zone produced by `calculate_window_rect` the window is in,
+/// `None` if the window isn't in any of the zones.
+pub fn find_window_zone(
+    monitor_rect: &Rect,
+    window_rect: &Rect,
+    window_margin: &WindowBorderSize,
+    gaps: &Gaps,
+) -> Option<HotKeyAction> {
+    MOVE_ACTIONS.into_iter().find(|action| {
+        let zone_rect = calculate_window_rect(monitor_rect, window_margin, gaps, action.clone());
+        (zone_rect.left - window_rect.left).abs() <= ZONE_MATCH_TOLERANCE
+            && (zone_rect.right - window_rect.right).abs() <= ZONE_MATCH_TOLERANCE
+            && (zone_rect.top - window_rect.top).abs() <= ZONE_MATCH_TOLERANCE
+            && (zone_rect.bottom - window_rect.bottom).abs() <= ZONE_MATCH_TOLERANCE
+    })
+}
+
+/// Like `calculate_window_rect`, but the window takes `fraction` of the monitor's width instead of
+/// half of it. For `MoveWindowToTop` and `MoveWindowToBottom` the fraction applies to the height.
+/// Windows on the right or bottom are anchored to the monitor's edge.
+pub fn calculate_window_rect_with_fraction(
+    monitor_rect: &Rect,
+    window_margin: &WindowBorderSize,
+    gaps: &Gaps,
+    action: HotKeyAction,
+    fraction: f32,
+) -> Rect {
+    let fraction = fraction.clamp(0.0, 1.0);
+    let monitor_width = monitor_rect.width();
+    let monitor_height = monitor_rect.height();
+    let part_width = (monitor_width as f32 * fraction).round() as i32;
+    let part_height = (monitor_height as f32 * fraction).round() as i32;
+    let half_height = monitor_height / 2;
 
-    let width = match action {
-        HotKeyAction::MoveWindowToBottom | HotKeyAction::MoveWindowToTop => monitor_rect.width(),
-        _ => (monitor_rect.width() / 2) + 1,
+    let (left, right) = match action {
+        HotKeyAction::MoveWindowToBottom | HotKeyAction::MoveWindowToTop => (0, monitor_width),
+        HotKeyAction::MoveWindowToRightBottom
+        | HotKeyAction::MoveWindowToRightMiddle
+        | HotKeyAction::MoveWindowToRightTop => (monitor_width - part_width, monitor_width),
+        _ => (0, part_width),
     };
 
-    let height = match action {
+    let (top, bottom) = match action {
         HotKeyAction::MoveWindowToLeftMiddle | HotKeyAction::MoveWindowToRightMiddle => {
-            monitor_rect.height()
+            (0, monitor_height)
+        }
+        HotKeyAction::MoveWindowToTop => (0, part_height),
+        HotKeyAction::MoveWindowToBottom => (monitor_height - part_height, monitor_height),
+        HotKeyAction::MoveWindowToLeftBottom | HotKeyAction::MoveWindowToRightBottom => {
+            (monitor_height - half_height, monitor_height)
         }
-        _ => monitor_rect.height() / 2,
+        _ => (0, half_height),
+    };
+
+    place_window_into_zone(
+        monitor_rect,
+        &offset_zone(monitor_rect, left, right, top, bottom),
+        window_margin,
+        gaps,
+    )
+}
+
+/// Calculates the rect of a window placed into `cell` of the monitor divided by `grid`.
+pub fn calculate_grid_cell_rect(
+    monitor_rect: &Rect,
+    window_margin: &WindowBorderSize,
+    gaps: &Gaps,
+    grid: &GridLayout,
+    cell: &GridCell,
+) -> Rect {
+    let columns = grid.columns.max(1) as i32;
+    let rows = grid.rows.max(1) as i32;
+    let first_column = (cell.column as i32).min(columns - 1);
+    let first_row = (cell.row as i32).min(rows - 1);
+    let last_column = (first_column + cell.column_span.max(1) as i32).min(columns);
+    let last_row = (first_row + cell.row_span.max(1) as i32).min(rows);
+
+    place_window_into_zone(
+        monitor_rect,
+        &offset_zone(
+            monitor_rect,
+            monitor_rect.width() * first_column / columns,
+            monitor_rect.width() * last_column / columns,
+            monitor_rect.height() * first_row / rows,
+            monitor_rect.height() * last_row / rows,
+        ),
+        window_margin,
+        gaps,
+    )
+}
+
+/// Calculates the rect of a window placed into a zone defined in the configuration.
+/// The zone is limited to the monitor's work area.
+pub fn calculate_zone_rect(
+    monitor_rect: &Rect,
+    window_margin: &WindowBorderSize,
+    gaps: &Gaps,
+    zone: &Zone,
+) -> Rect {
+    let resolve_size = |value: &ZoneValue, monitor_size: i32| match value {
+        ZoneValue::Pixels(pixels) => *pixels,
+        ZoneValue::Fraction(fraction) => (monitor_size as f32 * fraction).round() as i32,
+        ZoneValue::Center => monitor_size,
+    };
+    let resolve_position = |value: &ZoneValue, monitor_size: i32, size: i32| match value {
+        ZoneValue::Center => (monitor_size - size) / 2,
+        _ => resolve_size(value, monitor_size),
+    };
+
+    let monitor_width = monitor_rect.width();
+    let monitor_height = monitor_rect.height();
+    let width = resolve_size(&zone.width, monitor_width).clamp(0, monitor_width);
+    let height = resolve_size(&zone.height, monitor_height).clamp(0, monitor_height);
+    let left = resolve_position(&zone.x, monitor_width, width).clamp(0, monitor_width - width);
+    let top = resolve_position(&zone.y, monitor_height, height).clamp(0, monitor_height - height);
+
+    place_window_into_zone(
+        monitor_rect,
+        &offset_zone(monitor_rect, left, left + width, top, top + height),
+        window_margin,
+        gaps,
+    )
+}
+
+fn offset_zone(monitor_rect: &Rect, left: i32, right: i32, top: i32, bottom: i32) -> Rect {
+    Rect {
+        left: left + monitor_rect.left,
+        right: right + monitor_rect.left,
+        top: top + monitor_rect.top,
+        bottom: bottom + monitor_rect.top,
+    }
+}
+
+// Zones may end a pixel short of the monitor's edge due to rounding
+fn is_at_monitor_edge(edge: i32, monitor_edge: i32) -> bool {
+    (edge - monitor_edge).abs() <= 1
+}
+
+/// Calculates the window rect for a window occupying `zone` of the monitor.
+///
+/// Edges at the monitor's edge are moved inwards by the outer padding and the other edges by half
+/// of the inner gap. Without gaps the border compensation described above is applied instead.
+/// The result includes the window's extended frame given by `window_margin`.
+pub fn place_window_into_zone(
+    monitor_rect: &Rect,
+    zone: &Rect,
+    window_margin: &WindowBorderSize,
+    gaps: &Gaps,
+) -> Rect {
+    let outer_padding = gaps.outer_padding.max(0);
+    let inner_gap = gaps.inner_gap.max(0);
+    let bottom_compensation = if window_margin.bottom > 0 { 2 } else { 0 };
+
+    let left = match (
+        is_at_monitor_edge(zone.left, monitor_rect.left),
+        outer_padding,
+        inner_gap,
+    ) {
+        (true, 0, _) => zone.left,
+        (true, padding, _) => monitor_rect.left + padding,
+        (false, _, 0) => zone.left - 1,
+        (false, _, gap) => zone.left + gap / 2,
+    };
+    let right = match (
+        is_at_monitor_edge(zone.right, monitor_rect.right),
+        outer_padding,
+        inner_gap,
+    ) {
+        (true, 0, _) => zone.right,
+        (true, padding, _) => monitor_rect.right - padding,
+        (false, _, 0) => zone.right + 1,
+        (false, _, gap) => zone.right - (gap - gap / 2),
+    };
+    let top = match (
+        is_at_monitor_edge(zone.top, monitor_rect.top),
+        outer_padding,
+        inner_gap,
+    ) {
+        (true, 0, _) => zone.top,
+        (true, padding, _) => monitor_rect.top + padding,
+        (false, _, 0) => zone.top,
+        (false, _, gap) => zone.top + gap / 2,
+    };
+    let bottom = match (
+        is_at_monitor_edge(zone.bottom, monitor_rect.bottom),
+        outer_padding,
+        inner_gap,
+    ) {
+        (true, 0, _) => zone.bottom + bottom_compensation,
+        (true, padding, _) => monitor_rect.bottom - padding,
+        (false, _, 0) => zone.bottom + bottom_compensation,
+        (false, _, gap) => zone.bottom - (gap - gap / 2),
     };
 
-    Rect::from(&WindowPosition {
+    // The top of the extended frame isn't taken into account
+    Rect {
         left: left + window_margin.left,
+        right: right + window_margin.right,
         top,
-        width: width + window_margin.right - window_margin.left,
-        height: height + window_margin.bottom + (if window_margin.bottom > 0 { 2 } else { 0 }),
-    })
+        bottom: bottom + window_margin.bottom,
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use crate::common::hotkey_action::HotKeyAction::{
-        MoveWindowToRightBottom, MoveWindowToRightMiddle,
+        MoveWindowToLeftTop, MoveWindowToRightBottom, MoveWindowToRightMiddle,
     };
 
     use super::*;
@@ -77,6 +292,7 @@ mod tests {
                     rect: Default::default()
                 }),
                 &border,
+                &Gaps::default(),
                 MoveWindowToRightBottom,
             ),
             Rect::from(&WindowPosition {
@@ -98,6 +314,7 @@ mod tests {
                     rect: Default::default()
                 }),
                 &border,
+                &Gaps::default(),
                 MoveWindowToRightMiddle,
             ),
             Rect::from(&WindowPosition {
@@ -120,6 +337,7 @@ mod tests {
                     rect: Default::default()
                 }),
                 &border,
+                &Gaps::default(),
                 MoveWindowToRightBottom,
             ),
             Rect::from(&WindowPosition {
@@ -141,6 +359,7 @@ mod tests {
                     rect: Default::default()
                 }),
                 &border,
+                &Gaps::default(),
                 MoveWindowToRightMiddle,
             ),
             Rect::from(&WindowPosition {
@@ -169,6 +388,7 @@ mod tests {
                     top: -137,
                     bottom: -534,
                 },
+                &Gaps::default(),
                 MoveWindowToRightBottom,
             ),
             Rect::from(&WindowPosition {
@@ -195,6 +415,7 @@ mod tests {
                     top: -172,
                     bottom: -284,
                 },
+                &Gaps::default(),
                 MoveWindowToRightMiddle,
             ),
             Rect::from(&WindowPosition {
@@ -206,6 +427,309 @@ mod tests {
         );
     }
 
+    #[test]
+    fn fraction_calc_works() {
+        let border = WindowBorderSize {
+            left: -7,
+            right: 7,
+            top: 0,
+            bottom: 7,
+        };
+        let monitor = Rect {
+            left: 0,
+            right: 1920,
+            top: 0,
+            bottom: 1170,
+        };
+        assert_eq!(
+            calculate_window_rect_with_fraction(
+                &monitor,
+                &border,
+                &Gaps::default(),
+                MoveWindowToRightMiddle,
+                0.5
+            ),
+            calculate_window_rect(&monitor, &border, &Gaps::default(), MoveWindowToRightMiddle)
+        );
+        assert_eq!(
+            calculate_window_rect_with_fraction(
+                &monitor,
+                &border,
+                &Gaps::default(),
+                MoveWindowToRightMiddle,
+                0.333
+            ),
+            Rect::from(&WindowPosition {
+                left: 1273,
+                top: 0,
+                width: 654,
+                height: 1179,
+            })
+        );
+        assert_eq!(
+            calculate_window_rect_with_fraction(
+                &monitor,
+                &border,
+                &Gaps::default(),
+                MoveWindowToRightBottom,
+                0.667
+            ),
+            Rect::from(&WindowPosition {
+                left: 631,
+                top: 585,
+                width: 1296,
+                height: 594,
+            })
+        );
+    }
+
+    #[test]
+    fn gaps_calc_works() {
+        let border = WindowBorderSize {
+            left: -7,
+            right: 7,
+            top: 0,
+            bottom: 7,
+        };
+        let monitor = Rect {
+            left: 0,
+            right: 1920,
+            top: 0,
+            bottom: 1170,
+        };
+        let gaps = Gaps {
+            inner_gap: 9,
+            outer_padding: 20,
+        };
+        // Visible area of the window is 964..1900 x 589..1150
+        assert_eq!(
+            calculate_window_rect(&monitor, &border, &gaps, MoveWindowToRightBottom),
+            Rect {
+                left: 957,
+                right: 1907,
+                top: 589,
+                bottom: 1157,
+            }
+        );
+        // Visible area of the window is 20..955 x 20..580
+        assert_eq!(
+            calculate_window_rect(&monitor, &border, &gaps, MoveWindowToLeftTop),
+            Rect {
+                left: 13,
+                right: 962,
+                top: 20,
+                bottom: 587,
+            }
+        );
+    }
+
+    #[test]
+    fn zone_is_found_for_snapped_window() {
+        let border = WindowBorderSize {
+            left: -7,
+            right: 7,
+            top: 0,
+            bottom: 7,
+        };
+        let monitor = Rect {
+            left: -1920,
+            right: 0,
+            top: 0,
+            bottom: 1050,
+        };
+        let window_rect =
+            calculate_window_rect(&monitor, &border, &Gaps::default(), MoveWindowToLeftTop);
+        assert_eq!(
+            find_window_zone(&monitor, &window_rect, &border, &Gaps::default()),
+            Some(MoveWindowToLeftTop)
+        );
+        let moved_rect = Rect {
+            left: window_rect.left + 20,
+            ..window_rect
+        };
+        assert_eq!(
+            find_window_zone(&monitor, &moved_rect, &border, &Gaps::default()),
+            None
+        );
+    }
+
+    #[test]
+    fn zone_calc_works() {
+        let border = WindowBorderSize {
+            left: 0,
+            right: 0,
+            top: 0,
+            bottom: 0,
+        };
+        let monitor = Rect {
+            left: 1920,
+            right: 4480,
+            top: 0,
+            bottom: 1400,
+        };
+        let zone = |x, y, width, height| Zone {
+            name: "zone".to_string(),
+            monitor: None,
+            x,
+            y,
+            width,
+            height,
+        };
+        // Left 60%, the right edge is compensated as it's not at the monitor's edge
+        assert_eq!(
+            calculate_zone_rect(
+                &monitor,
+                &border,
+                &Gaps::default(),
+                &zone(
+                    ZoneValue::Pixels(0),
+                    ZoneValue::Pixels(0),
+                    ZoneValue::Fraction(0.6),
+                    ZoneValue::Fraction(1.0)
+                )
+            ),
+            Rect {
+                left: 1920,
+                right: 3457,
+                top: 0,
+                bottom: 1400,
+            }
+        );
+        // Centered 1280x720
+        assert_eq!(
+            calculate_zone_rect(
+                &monitor,
+                &border,
+                &Gaps::default(),
+                &zone(
+                    ZoneValue::Center,
+                    ZoneValue::Center,
+                    ZoneValue::Pixels(1280),
+                    ZoneValue::Pixels(720)
+                )
+            ),
+            Rect {
+                left: 2559,
+                right: 3841,
+                top: 340,
+                bottom: 1060,
+            }
+        );
+        // Zones larger than the monitor are limited to it
+        assert_eq!(
+            calculate_zone_rect(
+                &monitor,
+                &border,
+                &Gaps::default(),
+                &zone(
+                    ZoneValue::Pixels(2000),
+                    ZoneValue::Pixels(0),
+                    ZoneValue::Pixels(5000),
+                    ZoneValue::Fraction(0.5)
+                )
+            ),
+            Rect {
+                left: 1920,
+                right: 4480,
+                top: 0,
+                bottom: 700,
+            }
+        );
+    }
+
+    #[test]
+    fn grid_cell_calc_matches_halves() {
+        let border = WindowBorderSize {
+            left: -7,
+            right: 7,
+            top: 0,
+            bottom: 7,
+        };
+        let monitor = Rect {
+            left: 0,
+            right: 1920,
+            top: 0,
+            bottom: 1170,
+        };
+        let grid = GridLayout {
+            monitor: None,
+            columns: 2,
+            rows: 2,
+        };
+        let cell = GridCell {
+            column: 1,
+            row: 1,
+            column_span: 1,
+            row_span: 1,
+        };
+        assert_eq!(
+            calculate_grid_cell_rect(&monitor, &border, &Gaps::default(), &grid, &cell),
+            calculate_window_rect(&monitor, &border, &Gaps::default(), MoveWindowToRightBottom)
+        );
+    }
+
+    #[test]
+    fn grid_cell_calc_works_for_thirds() {
+        let border = WindowBorderSize {
+            left: 0,
+            right: 0,
+            top: 0,
+            bottom: 0,
+        };
+        let monitor = Rect {
+            left: 0,
+            right: 3440,
+            top: 0,
+            bottom: 1400,
+        };
+        let grid = GridLayout {
+            monitor: None,
+            columns: 3,
+            rows: 2,
+        };
+        assert_eq!(
+            calculate_grid_cell_rect(
+                &monitor,
+                &border,
+                &Gaps::default(),
+                &grid,
+                &GridCell {
+                    column: 1,
+                    row: 0,
+                    column_span: 1,
+                    row_span: 2,
+                }
+            ),
+            Rect::from(&WindowPosition {
+                left: 1145,
+                top: 0,
+                width: 1149,
+                height: 1400,
+            })
+        );
+        // Spans reaching over the grid are cut at the monitor's edge
+        assert_eq!(
+            calculate_grid_cell_rect(
+                &monitor,
+                &border,
+                &Gaps::default(),
+                &grid,
+                &GridCell {
+                    column: 1,
+                    row: 1,
+                    column_span: 5,
+                    row_span: 1,
+                }
+            ),
+            Rect::from(&WindowPosition {
+                left: 1145,
+                top: 700,
+                width: 2295,
+                height: 700,
+            })
+        );
+    }
+
     // TODO: remporary
     impl From<&MonitorInfo> for Rect {
         fn from(value: &MonitorInfo) -> Self {
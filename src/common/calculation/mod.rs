@@ -1 +1,2 @@
+pub mod calculate_resized_window_rect;
 pub mod calculate_window_rect;
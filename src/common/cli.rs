@@ -0,0 +1,220 @@
+use crate::common::action_registry::parse_action;
+use crate::common::config::Config;
+use crate::common::hotkey_action::HotKeyAction;
+use crate::common::traits::Monitor;
+use std::path::PathBuf;
+
+pub const USAGE: &str = "\
+Usage: win-move [--config <path>] [command]
+
+Commands:
+  run                        Register the hotkeys and wait for them to be pressed (default)
+  exec <action> [name=value]...
+                             Perform an action on the foreground window and exit,
+                             eg. `win-move exec MoveToMonitor index=1`
+  list-actions               List the actions and their parameters
+  list-keys                  List the key names that can be used in hotkeys
+  list-monitors              List the monitors with their indexes and names
+  check-config [path]        Check the configuration file for mistakes
+  config-path                Print the path of the configuration file
+  help                       Print this help
+";
+
+/// Subcommand given on the command line
+#[derive(Debug, Clone, PartialEq)]
+pub enum Command {
+    Run,
+    Exec(HotKeyAction),
+    ListActions,
+    ListKeys,
+    ListMonitors,
+    CheckConfig(Option<PathBuf>),
+    ConfigPath,
+    Help,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct CommandLine {
+    /// Configuration file given with `--config`
+    pub config_path: Option<PathBuf>,
+    pub command: Command,
+}
+
+/// Parses the command line arguments, without the name of the executable
+pub fn parse_command_line(mut args: Vec<String>) -> Result<CommandLine, String> {
+    let config_path = take_config_flag(&mut args)?;
+    let mut args = args.into_iter();
+    let command = match args.next().as_deref() {
+        None | Some("run") => Command::Run,
+        Some("exec") => {
+            let name = args
+                .next()
+                .ok_or("exec requires an action, run `win-move list-actions` for the actions")?;
+            Command::Exec(parse_action(&name, parse_parameters(args.by_ref())?)?)
+        }
+        Some("list-actions") => Command::ListActions,
+        Some("list-keys") => Command::ListKeys,
+        Some("list-monitors") => Command::ListMonitors,
+        Some("check-config") => Command::CheckConfig(args.next().map(PathBuf::from)),
+        Some("config-path") => Command::ConfigPath,
+        Some("help" | "--help" | "-h") => Command::Help,
+        Some(command) => return Err(format!("unknown command '{}'", command)),
+    };
+
+    if let Some(extra) = args.next() {
+        return Err(format!("unexpected argument '{}'", extra));
+    }
+    Ok(CommandLine {
+        config_path,
+        command,
+    })
+}
+
+// Removes `--config <path>` or `--config=<path>` from the arguments, returning the path
+fn take_config_flag(args: &mut Vec<String>) -> Result<Option<PathBuf>, String> {
+    let Some(index) = args
+        .iter()
+        .position(|arg| arg == "--config" || arg.starts_with("--config="))
+    else {
+        return Ok(None);
+    };
+
+    let flag = args.remove(index);
+    let path = match flag.strip_prefix("--config=") {
+        Some(path) => path.to_string(),
+        None if index < args.len() => args.remove(index),
+        None => String::new(),
+    };
+    if path.is_empty() {
+        return Err("--config requires a path".to_string());
+    }
+    Ok(Some(PathBuf::from(path)))
+}
+
+// Reads action parameters given as `name=value`. Values are read as TOML values, so numbers and
+// booleans keep their type, anything else is a string: `index=1`, `step=5%`, `name="left"`.
+fn parse_parameters(args: impl Iterator<Item = String>) -> Result<toml::Table, String> {
+    let mut parameters = toml::Table::new();
+    for arg in args {
+        let (name, value) = arg
+            .split_once('=')
+            .ok_or_else(|| format!("expected a parameter as name=value, got '{}'", arg))?;
+        let value = format!("value = {}", value)
+            .parse::<toml::Table>()
+            .ok()
+            .and_then(|mut table| table.remove("value"))
+            .unwrap_or_else(|| toml::Value::String(value.to_string()));
+        parameters.insert(name.to_string(), value);
+    }
+    Ok(parameters)
+}
+
+/// One line per monitor, in the configured monitor order, with its index, work area and the name
+/// given to it in the configuration
+pub fn format_monitor_list(
+    monitors: &[Box<dyn Monitor>],
+    config: &Config,
+) -> crate::Result<String> {
+    let mut list = String::new();
+    for (index, monitor) in monitors.iter().enumerate() {
+        let size = monitor.get_size()?;
+        list += &format!(
+            "{}  {}x{} at ({}, {})",
+            index,
+            size.width(),
+            size.height(),
+            size.left,
+            size.top
+        );
+        let name = config
+            .monitors
+            .iter()
+            .find(|monitor| monitor.index == index)
+            .and_then(|monitor| monitor.name.as_deref());
+        if let Some(name) = name {
+            list += &format!("  \"{}\"", name);
+        }
+        list += "\n";
+    }
+    Ok(list)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::enums::ResizeDirection;
+    use crate::common::structs::{MonitorTarget, ResizeStep};
+
+    fn parse(args: &[&str]) -> Result<CommandLine, String> {
+        parse_command_line(args.iter().map(|arg| arg.to_string()).collect())
+    }
+
+    fn command(args: &[&str]) -> Result<Command, String> {
+        parse(args).map(|command_line| command_line.command)
+    }
+
+    #[test]
+    fn parses_commands() {
+        assert_eq!(command(&[]), Ok(Command::Run));
+        assert_eq!(command(&["run"]), Ok(Command::Run));
+        assert_eq!(command(&["list-monitors"]), Ok(Command::ListMonitors));
+        assert_eq!(
+            command(&["check-config", "other.toml"]),
+            Ok(Command::CheckConfig(Some(PathBuf::from("other.toml"))))
+        );
+        assert_eq!(
+            parse(&["--config", "my.toml", "run"]),
+            Ok(CommandLine {
+                config_path: Some(PathBuf::from("my.toml")),
+                command: Command::Run,
+            })
+        );
+        assert_eq!(
+            parse(&["list-actions", "--config=my.toml"]).map(|command| command.config_path),
+            Ok(Some(PathBuf::from("my.toml")))
+        );
+        assert!(parse(&["--config"]).is_err());
+        assert!(command(&["runn"]).is_err());
+        assert!(command(&["run", "now"]).is_err());
+    }
+
+    #[test]
+    fn parses_exec_actions() {
+        assert_eq!(
+            command(&["exec", "MaximizeWindow"]),
+            Ok(Command::Exec(HotKeyAction::MaximizeWindow))
+        );
+        assert_eq!(
+            command(&["exec", "MoveToMonitor", "index=1"]),
+            Ok(Command::Exec(HotKeyAction::MoveWindowToMonitor(
+                MonitorTarget::Index(1)
+            )))
+        );
+        assert_eq!(
+            command(&["exec", "MoveToMonitor", "name=left"]),
+            Ok(Command::Exec(HotKeyAction::MoveWindowToMonitor(
+                MonitorTarget::Name("left".to_string())
+            )))
+        );
+        assert_eq!(
+            command(&[
+                "exec",
+                "Resize",
+                "direction=Left",
+                "step=5%",
+                "history_aware=true"
+            ]),
+            Ok(Command::Exec(HotKeyAction::ResizeWindow {
+                direction: ResizeDirection::Left,
+                step: ResizeStep::Fraction(0.05),
+                history_aware: true,
+            }))
+        );
+        assert!(command(&["exec"]).is_err());
+        assert!(command(&["exec", "MoveToMonitor", "index"]).is_err());
+        assert_eq!(
+            command(&["exec", "MoveWindowToNowhere"]),
+            Err("unknown action 'MoveWindowToNowhere'".to_string())
+        );
+    }
+}
@@ -1,12 +1,104 @@
-use crate::common::enums::{HotKeyButton, HotKeyModifier};
+use crate::common::config_validation::validate_config;
+use crate::common::enums::{HotKeyButton, MonitorOrder};
+use crate::common::hotkey::{HotKeyModifiers, Hotkey};
 use crate::common::hotkey_action::HotKeyAction;
-use crate::common::structs::HotkeyMapping;
+use crate::common::structs::{Gaps, GridLayout, HotkeyMapping, MonitorConfig, SizeCycle, Zone};
 use std::fs;
-use std::path::PathBuf;
+use std::path::Path;
+use std::time::Duration;
 
 #[derive(Debug, serde::Serialize, serde::Deserialize)]
 pub struct Config {
     pub hotkeys: Vec<HotkeyMapping>,
+    #[serde(default)]
+    pub grids: Vec<GridLayout>,
+    #[serde(default)]
+    pub size_cycles: Vec<SizeCycle>,
+    /// Time in milliseconds within which pressing the same move hotkey again cycles the size
+    #[serde(default = "default_size_cycle_timeout_ms")]
+    pub size_cycle_timeout_ms: u64,
+    /// Space between snapped windows, in pixels
+    #[serde(default)]
+    pub inner_gap: i32,
+    /// Space between snapped windows and the monitor's edges, in pixels
+    #[serde(default)]
+    pub outer_padding: i32,
+    /// Order in which monitors are numbered, used by all monitor indexes in the configuration
+    #[serde(default)]
+    pub monitor_order: MonitorOrder,
+    #[serde(default)]
+    pub monitors: Vec<MonitorConfig>,
+    #[serde(default)]
+    pub zones: Vec<Zone>,
+    /// Refuse to start if any hotkey can't be registered
+    #[serde(default)]
+    pub strict_hotkeys: bool,
+}
+
+fn default_size_cycle_timeout_ms() -> u64 {
+    2000
+}
+
+impl Config {
+    /// Returns the grid layout of the monitor at `monitor_index`. Falls back to the layout without
+    /// a monitor and then to the default 2x2 grid.
+    pub fn get_grid_layout(&self, monitor_index: Option<usize>) -> GridLayout {
+        self.grids
+            .iter()
+            .find(|grid| grid.monitor.is_some() && grid.monitor == monitor_index)
+            .or_else(|| self.grids.iter().find(|grid| grid.monitor.is_none()))
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    /// Returns the size fractions configured for `action`, empty if size cycling isn't enabled for it
+    pub fn get_size_cycle_fractions(&self, action: &HotKeyAction) -> &[f32] {
+        self.size_cycles
+            .iter()
+            .find(|cycle| cycle.action == *action)
+            .map(|cycle| cycle.fractions.as_slice())
+            .unwrap_or_default()
+    }
+
+    /// Returns the gaps of the monitor at `monitor_index`, monitor specific settings override the
+    /// global ones
+    pub fn get_gaps(&self, monitor_index: Option<usize>) -> Gaps {
+        let monitor = self
+            .monitors
+            .iter()
+            .find(|monitor| Some(monitor.index) == monitor_index);
+        Gaps {
+            inner_gap: monitor
+                .and_then(|monitor| monitor.inner_gap)
+                .unwrap_or(self.inner_gap),
+            outer_padding: monitor
+                .and_then(|monitor| monitor.outer_padding)
+                .unwrap_or(self.outer_padding),
+        }
+    }
+
+    /// Returns the zone called `name` on the monitor at `monitor_index`. Zones defined for the
+    /// monitor are preferred over zones for all monitors, and those over zones of other monitors.
+    pub fn find_zone(&self, name: &str, monitor_index: Option<usize>) -> Option<&Zone> {
+        let mut zones = self.zones.iter().filter(|zone| zone.name == name);
+        zones
+            .clone()
+            .find(|zone| zone.monitor.is_some() && zone.monitor == monitor_index)
+            .or_else(|| zones.clone().find(|zone| zone.monitor.is_none()))
+            .or_else(|| zones.next())
+    }
+
+    /// Returns the index of the monitor given the name `name` in `[[monitors]]`
+    pub fn get_monitor_index_by_name(&self, name: &str) -> Option<usize> {
+        self.monitors
+            .iter()
+            .find(|monitor| monitor.name.as_deref() == Some(name))
+            .map(|monitor| monitor.index)
+    }
+
+    pub fn get_size_cycle_timeout(&self) -> Duration {
+        Duration::from_millis(self.size_cycle_timeout_ms)
+    }
 }
 
 impl Default for Config {
@@ -15,103 +107,152 @@ impl Default for Config {
             hotkeys: vec![
                 HotkeyMapping {
                     action: HotKeyAction::MoveWindowToLeftBottom,
-                    key: HotKeyButton::VkNumpad1,
-                    modifier: HotKeyModifier::ModControl,
+                    hotkey: Hotkey {
+                        key: HotKeyButton::VkNumpad1,
+                        modifiers: HotKeyModifiers::CONTROL,
+                    },
                 },
                 HotkeyMapping {
                     action: HotKeyAction::MoveWindowToBottom,
-                    key: HotKeyButton::VkNumpad2,
-                    modifier: HotKeyModifier::ModControl,
+                    hotkey: Hotkey {
+                        key: HotKeyButton::VkNumpad2,
+                        modifiers: HotKeyModifiers::CONTROL,
+                    },
                 },
                 HotkeyMapping {
                     action: HotKeyAction::MoveWindowToRightBottom,
-                    key: HotKeyButton::VkNumpad3,
-                    modifier: HotKeyModifier::ModControl,
+                    hotkey: Hotkey {
+                        key: HotKeyButton::VkNumpad3,
+                        modifiers: HotKeyModifiers::CONTROL,
+                    },
                 },
                 HotkeyMapping {
                     action: HotKeyAction::MoveWindowToLeftMiddle,
-                    key: HotKeyButton::VkNumpad4,
-                    modifier: HotKeyModifier::ModControl,
+                    hotkey: Hotkey {
+                        key: HotKeyButton::VkNumpad4,
+                        modifiers: HotKeyModifiers::CONTROL,
+                    },
                 },
                 HotkeyMapping {
                     action: HotKeyAction::MoveWindowToRightMiddle,
-                    key: HotKeyButton::VkNumpad6,
-                    modifier: HotKeyModifier::ModControl,
+                    hotkey: Hotkey {
+                        key: HotKeyButton::VkNumpad6,
+                        modifiers: HotKeyModifiers::CONTROL,
+                    },
                 },
                 HotkeyMapping {
                     action: HotKeyAction::MoveWindowToLeftTop,
-                    key: HotKeyButton::VkNumpad7,
-                    modifier: HotKeyModifier::ModControl,
+                    hotkey: Hotkey {
+                        key: HotKeyButton::VkNumpad7,
+                        modifiers: HotKeyModifiers::CONTROL,
+                    },
                 },
                 HotkeyMapping {
                     action: HotKeyAction::MoveWindowToTop,
-                    key: HotKeyButton::VkNumpad8,
-                    modifier: HotKeyModifier::ModControl,
+                    hotkey: Hotkey {
+                        key: HotKeyButton::VkNumpad8,
+                        modifiers: HotKeyModifiers::CONTROL,
+                    },
                 },
                 HotkeyMapping {
                     action: HotKeyAction::MoveWindowToRightTop,
-                    key: HotKeyButton::VkNumpad9,
-                    modifier: HotKeyModifier::ModControl,
+                    hotkey: Hotkey {
+                        key: HotKeyButton::VkNumpad9,
+                        modifiers: HotKeyModifiers::CONTROL,
+                    },
                 },
                 HotkeyMapping {
                     action: HotKeyAction::MoveWindowToLeftScreenContinuous,
-                    key: HotKeyButton::VkNumpad0,
-                    modifier: HotKeyModifier::ModControl,
+                    hotkey: Hotkey {
+                        key: HotKeyButton::VkNumpad0,
+                        modifiers: HotKeyModifiers::CONTROL,
+                    },
                 },
                 HotkeyMapping {
                     action: HotKeyAction::MinimizeWindow,
-                    key: HotKeyButton::VkDecimal,
-                    modifier: HotKeyModifier::ModControl,
+                    hotkey: Hotkey {
+                        key: HotKeyButton::VkDecimal,
+                        modifiers: HotKeyModifiers::CONTROL,
+                    },
                 },
                 HotkeyMapping {
                     action: HotKeyAction::MaximizeWindow,
-                    key: HotKeyButton::VkNumpad5,
-                    modifier: HotKeyModifier::ModControl,
+                    hotkey: Hotkey {
+                        key: HotKeyButton::VkNumpad5,
+                        modifiers: HotKeyModifiers::CONTROL,
+                    },
                 },
             ],
+            grids: vec![],
+            size_cycles: vec![],
+            size_cycle_timeout_ms: default_size_cycle_timeout_ms(),
+            inner_gap: 0,
+            outer_padding: 0,
+            monitor_order: MonitorOrder::default(),
+            monitors: vec![],
+            zones: vec![],
+            strict_hotkeys: false,
         }
     }
 }
 
-fn get_config_path() -> PathBuf {
-    let exe_path = std::env::current_exe().unwrap_or_default();
-    let exe_dir = exe_path.parent().unwrap_or(std::path::Path::new("."));
-    exe_dir.join("config.toml")
-}
-
-pub fn load_config() -> Config {
-    let config_path = get_config_path();
-
-    if let Ok(contents) = fs::read_to_string(&config_path) {
-        match toml::from_str(&contents) {
-            Ok(config) => {
-                log::info!("Loaded configuration from {:?}", config_path);
-                return config;
-            }
-            Err(e) => {
-                log::warn!(
-                    "Failed to parse config file: {}. Using default configuration.",
-                    e
-                );
-            }
-        }
-    } else {
+/// Reads the configuration file, falling back to the default configuration if it can't be used
+pub fn load_config(config_path: &Path) -> Config {
+    let Ok(contents) = fs::read_to_string(config_path) else {
         log::info!(
             "No config file found at {:?}. Using default configuration.",
             config_path
         );
-    }
+        return Config::default();
+    };
 
-    Config::default()
+    let validated = validate_config(&contents);
+    for diagnostic in &validated.diagnostics {
+        log::warn!("{}", diagnostic.format_with_path(config_path));
+    }
+    match validated.config {
+        Some(config) => {
+            log::info!("Loaded configuration from {:?}", config_path);
+            config
+        }
+        None => {
+            log::warn!("Failed to parse config file. Using default configuration.");
+            Config::default()
+        }
+    }
 }
 
-pub fn get_config_hotkeys() -> Vec<HotkeyMapping> {
-    load_config().hotkeys
+/// Reads the configuration file again after it changed. Returns `None`, keeping the current
+/// configuration, if the file can't be read or has any problems.
+pub fn reload_config(config_path: &Path) -> Option<Config> {
+    let contents = match fs::read_to_string(config_path) {
+        Ok(contents) => contents,
+        Err(error) => {
+            log::warn!(
+                "Couldn't read config file {:?}: {}. Keeping the current configuration.",
+                config_path,
+                error
+            );
+            return None;
+        }
+    };
+
+    let validated = validate_config(&contents);
+    for diagnostic in &validated.diagnostics {
+        log::warn!("{}", diagnostic.format_with_path(config_path));
+    }
+    if !validated.is_valid() {
+        log::warn!("Config file has errors. Keeping the current configuration.");
+        return None;
+    }
+    log::info!("Reloaded configuration from {:?}", config_path);
+    validated.config
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::common::structs::{GridCell, MonitorTarget, ZoneValue};
 
     #[test]
     fn test_default_config_has_hotkeys() {
@@ -149,4 +290,281 @@ modifier = "ModControl"
         );
         assert_eq!(config.hotkeys[1].action, HotKeyAction::MoveWindowToTop);
     }
+
+    #[test]
+    fn test_key_deserialization() {
+        let toml_str = r#"
+[[hotkeys]]
+action = "MoveWindowToLeftMiddle"
+key = "VkNumpad4"
+modifier = "ModControl"
+
+[[hotkeys]]
+action = "MoveWindowToLeftMiddle"
+key = "VkLeft"
+modifier = "ModAlt"
+
+[[hotkeys]]
+action = "MaximizeWindow"
+key = "VkF11"
+modifier = "ModAlt"
+
+[[hotkeys]]
+action = "MinimizeWindow"
+key = "VkLeftBracket"
+modifier = "ModAlt"
+"#;
+        let config: Config = toml::from_str(toml_str).unwrap();
+        let keys: Vec<HotKeyButton> = config.hotkeys.into_iter().map(|h| h.hotkey.key).collect();
+        assert_eq!(
+            keys,
+            vec![
+                HotKeyButton::VkNumpad4,
+                HotKeyButton::VkLeft,
+                HotKeyButton::VkF11,
+                HotKeyButton::VkLeftBracket
+            ]
+        );
+    }
+
+    #[test]
+    fn test_hotkey_string_deserialization() {
+        let toml_str = r#"
+[[hotkeys]]
+action = "MoveWindowToLeftMiddle"
+hotkey = "Ctrl+Alt+Left"
+
+[[hotkeys]]
+action = "MoveWindowToLeftTop"
+hotkey = "Super+Shift+KP_7"
+"#;
+        let config: Config = toml::from_str(toml_str).unwrap();
+        assert_eq!(config.hotkeys[0].hotkey.key, HotKeyButton::VkLeft);
+        assert_eq!(
+            config.hotkeys[0].hotkey.modifiers,
+            HotKeyModifiers {
+                control: true,
+                alt: true,
+                ..HotKeyModifiers::NONE
+            }
+        );
+        assert_eq!(config.hotkeys[1].hotkey.key, HotKeyButton::VkNumpad7);
+        assert!(config.hotkeys[1].hotkey.modifiers.super_key);
+
+        let error = toml::from_str::<Config>(
+            r#"
+[[hotkeys]]
+action = "MoveWindowToLeftMiddle"
+hotkey = "Ctrl+Lfet"
+"#,
+        )
+        .unwrap_err();
+        assert!(error.message().contains("unknown key 'Lfet'"));
+
+        let error = toml::from_str::<Config>(
+            r#"
+[[hotkeys]]
+action = "MoveWindowToLeftMiddle"
+hotkey = "Ctrl+Left"
+key = "VkLeft"
+"#,
+        )
+        .unwrap_err();
+        assert!(error.message().contains("can't be combined"));
+    }
+
+    #[test]
+    fn test_example_config_deserialization() {
+        let config: Config = toml::from_str(include_str!("../../config.toml.example")).unwrap();
+        assert_eq!(config.hotkeys, Config::default().hotkeys);
+    }
+
+    #[test]
+    fn test_grid_config_deserialization() {
+        let toml_str = r#"
+[[hotkeys]]
+action = { type = "MoveToGridCell", column = 1, row = 0, row_span = 2 }
+key = "VkNumpad8"
+modifier = "ModAlt"
+
+[[grids]]
+columns = 3
+rows = 2
+
+[[grids]]
+monitor = 1
+columns = 2
+rows = 1
+"#;
+        let config: Config = toml::from_str(toml_str).unwrap();
+        assert_eq!(
+            config.hotkeys[0].action,
+            HotKeyAction::MoveWindowToGridCell(GridCell {
+                column: 1,
+                row: 0,
+                column_span: 1,
+                row_span: 2,
+            })
+        );
+        assert_eq!(config.get_grid_layout(Some(0)).columns, 3);
+        assert_eq!(config.get_grid_layout(Some(1)).columns, 2);
+        assert_eq!(Config::default().get_grid_layout(Some(1)).columns, 2);
+    }
+
+    #[test]
+    fn test_size_cycle_config_deserialization() {
+        let toml_str = r#"
+hotkeys = []
+size_cycle_timeout_ms = 1500
+
+[[size_cycles]]
+action = "MoveWindowToLeftMiddle"
+fractions = [0.5, 0.333, 0.667]
+"#;
+        let config: Config = toml::from_str(toml_str).unwrap();
+        assert_eq!(
+            config.get_size_cycle_fractions(&HotKeyAction::MoveWindowToLeftMiddle),
+            &[0.5, 0.333, 0.667]
+        );
+        assert!(config
+            .get_size_cycle_fractions(&HotKeyAction::MoveWindowToRightMiddle)
+            .is_empty());
+        assert_eq!(config.get_size_cycle_timeout(), Duration::from_millis(1500));
+    }
+
+    #[test]
+    fn test_gaps_config_deserialization() {
+        let toml_str = r#"
+hotkeys = []
+inner_gap = 8
+outer_padding = 16
+
+[[monitors]]
+index = 1
+outer_padding = 0
+"#;
+        let config: Config = toml::from_str(toml_str).unwrap();
+        assert_eq!(
+            config.get_gaps(Some(0)),
+            Gaps {
+                inner_gap: 8,
+                outer_padding: 16
+            }
+        );
+        assert_eq!(
+            config.get_gaps(Some(1)),
+            Gaps {
+                inner_gap: 8,
+                outer_padding: 0
+            }
+        );
+        assert_eq!(Config::default().get_gaps(None), Gaps::default());
+    }
+
+    #[test]
+    fn test_move_to_monitor_config_deserialization() {
+        let toml_str = r#"
+monitor_order = "TopToBottom"
+
+[[hotkeys]]
+action = { type = "MoveToMonitor", index = 2 }
+key = "VkNumpad2"
+modifier = "ModAlt"
+
+[[hotkeys]]
+action = { type = "MoveToMonitor", name = "center" }
+key = "VkNumpad5"
+modifier = "ModAlt"
+
+[[monitors]]
+index = 1
+name = "center"
+"#;
+        let config: Config = toml::from_str(toml_str).unwrap();
+        assert_eq!(config.monitor_order, MonitorOrder::TopToBottom);
+        assert_eq!(
+            config.hotkeys[0].action,
+            HotKeyAction::MoveWindowToMonitor(MonitorTarget::Index(2))
+        );
+        assert_eq!(
+            config.hotkeys[1].action,
+            HotKeyAction::MoveWindowToMonitor(MonitorTarget::Name("center".to_string()))
+        );
+        assert_eq!(config.get_monitor_index_by_name("center"), Some(1));
+        assert_eq!(config.get_monitor_index_by_name("left"), None);
+    }
+
+    #[test]
+    fn test_zone_config_deserialization() {
+        let toml_str = r#"
+[[hotkeys]]
+action = { type = "MoveToZone", zone = "main" }
+key = "VkNumpad4"
+modifier = "ModAlt"
+
+[[zones]]
+name = "main"
+x = 0
+y = 0
+width = "60%"
+height = "100%"
+
+[[zones]]
+name = "main"
+monitor = 1
+x = "center"
+y = "center"
+width = 1280
+height = "720px"
+"#;
+        let config: Config = toml::from_str(toml_str).unwrap();
+        assert_eq!(
+            config.hotkeys[0].action,
+            HotKeyAction::MoveWindowToZone {
+                zone: "main".to_string()
+            }
+        );
+        let zone = config.find_zone("main", Some(0)).unwrap();
+        assert_eq!(zone.width, ZoneValue::Fraction(0.6));
+        let zone = config.find_zone("main", Some(1)).unwrap();
+        assert_eq!(zone.x, ZoneValue::Center);
+        assert_eq!(zone.height, ZoneValue::Pixels(720));
+        assert!(config.find_zone("side", Some(0)).is_none());
+
+        let invalid = r#"
+hotkeys = []
+
+[[zones]]
+name = "main"
+x = 0
+y = 0
+width = "sixty"
+height = "100%"
+"#;
+        assert!(toml::from_str::<Config>(invalid).is_err());
+    }
+
+    #[test]
+    fn reload_keeps_current_config_on_errors() {
+        let path =
+            std::env::temp_dir().join(format!("win-move-reload-{}.toml", std::process::id()));
+
+        fs::write(
+            &path,
+            "[[hotkeys]]\naction = \"MaximizeWindow\"\nhotkey = \"Ctrl+M\"\n",
+        )
+        .unwrap();
+        let config = reload_config(&path).unwrap();
+        assert_eq!(config.hotkeys.len(), 1);
+
+        fs::write(
+            &path,
+            "[[hotkeys]]\naction = \"MaximizeWindow\"\nhotkey = \"Ctrl+Mm\"\n",
+        )
+        .unwrap();
+        assert!(reload_config(&path).is_none());
+
+        fs::remove_file(&path).unwrap();
+        assert!(reload_config(&path).is_none());
+    }
 }
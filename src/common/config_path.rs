@@ -0,0 +1,177 @@
+use std::path::{Path, PathBuf};
+
+/// Environment variable that can point to the configuration file
+pub const CONFIG_ENV_VAR: &str = "WIN_MOVE_CONFIG";
+
+const CONFIG_FILE_NAME: &str = "config.toml";
+const CONFIG_DIR_NAME: &str = "win-move";
+
+/// Where the configuration file path came from
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum ConfigSource {
+    CommandLine,
+    Environment,
+    UserConfigDir,
+    ExecutableDir,
+    /// No configuration file exists, the path is where one would be read from
+    NotFound,
+}
+
+impl std::fmt::Display for ConfigSource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let description = match self {
+            ConfigSource::CommandLine => "given with --config",
+            ConfigSource::Environment => "given with WIN_MOVE_CONFIG",
+            ConfigSource::UserConfigDir => "found in the user configuration directory",
+            ConfigSource::ExecutableDir => "found next to the executable",
+            ConfigSource::NotFound => "not found, using the default configuration",
+        };
+        write!(f, "{}", description)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConfigPath {
+    pub path: PathBuf,
+    pub source: ConfigSource,
+}
+
+/// `config.toml` in the per-user configuration directory: `$XDG_CONFIG_HOME/win-move` (or
+/// `~/.config/win-move`) on Linux, `%APPDATA%\win-move` on Windows
+pub fn get_user_config_path() -> Option<PathBuf> {
+    let config_dir = if cfg!(windows) {
+        env_path("APPDATA")
+    } else {
+        env_path("XDG_CONFIG_HOME").or_else(|| env_path("HOME").map(|home| home.join(".config")))
+    };
+    config_dir.map(|dir| dir.join(CONFIG_DIR_NAME).join(CONFIG_FILE_NAME))
+}
+
+/// `config.toml` in the same directory as the executable
+pub fn get_executable_config_path() -> Option<PathBuf> {
+    let exe_path = std::env::current_exe().ok()?;
+    Some(exe_path.parent()?.join(CONFIG_FILE_NAME))
+}
+
+// Value of an environment variable holding a path, ignoring unset and empty variables
+fn env_path(name: &str) -> Option<PathBuf> {
+    std::env::var_os(name)
+        .filter(|value| !value.is_empty())
+        .map(PathBuf::from)
+}
+
+/// Finds the configuration file. The locations are searched in order:
+/// 1. `--config <path>` given on the command line
+/// 2. The `WIN_MOVE_CONFIG` environment variable
+/// 3. The per-user configuration directory, see [`get_user_config_path`]
+/// 4. The directory of the executable
+///
+/// Paths given explicitly are used even if the file doesn't exist.
+pub fn find_config_path(command_line_path: Option<PathBuf>) -> ConfigPath {
+    resolve_config_path(
+        command_line_path,
+        env_path(CONFIG_ENV_VAR),
+        get_user_config_path(),
+        get_executable_config_path(),
+        |path| path.is_file(),
+    )
+}
+
+fn resolve_config_path(
+    command_line_path: Option<PathBuf>,
+    env_var_path: Option<PathBuf>,
+    user_config_path: Option<PathBuf>,
+    executable_config_path: Option<PathBuf>,
+    exists: impl Fn(&Path) -> bool,
+) -> ConfigPath {
+    let explicit = [
+        (command_line_path, ConfigSource::CommandLine),
+        (env_var_path, ConfigSource::Environment),
+    ];
+    let searched = [
+        (user_config_path.clone(), ConfigSource::UserConfigDir),
+        (executable_config_path.clone(), ConfigSource::ExecutableDir),
+    ];
+
+    explicit
+        .into_iter()
+        .filter_map(|(path, source)| {
+            Some(ConfigPath {
+                path: path?,
+                source,
+            })
+        })
+        .chain(searched.into_iter().filter_map(|(path, source)| {
+            let path = path.filter(|path| exists(path))?;
+            Some(ConfigPath { path, source })
+        }))
+        .next()
+        .unwrap_or_else(|| ConfigPath {
+            // Where a new configuration file should be created
+            path: user_config_path
+                .or(executable_config_path)
+                .unwrap_or_else(|| PathBuf::from(CONFIG_FILE_NAME)),
+            source: ConfigSource::NotFound,
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn resolve(command_line: Option<&str>, env_var: Option<&str>, existing: &[&str]) -> ConfigPath {
+        resolve_config_path(
+            command_line.map(PathBuf::from),
+            env_var.map(PathBuf::from),
+            Some(PathBuf::from("/home/user/.config/win-move/config.toml")),
+            Some(PathBuf::from("/opt/win-move/config.toml")),
+            |path| existing.iter().any(|existing| Path::new(existing) == path),
+        )
+    }
+
+    #[test]
+    fn searches_locations_in_order() {
+        let all = [
+            "/home/user/.config/win-move/config.toml",
+            "/opt/win-move/config.toml",
+        ];
+        assert_eq!(
+            resolve(Some("cli.toml"), Some("env.toml"), &all),
+            ConfigPath {
+                path: PathBuf::from("cli.toml"),
+                source: ConfigSource::CommandLine
+            }
+        );
+        assert_eq!(
+            resolve(None, Some("env.toml"), &all).source,
+            ConfigSource::Environment
+        );
+        assert_eq!(
+            resolve(None, None, &all).source,
+            ConfigSource::UserConfigDir
+        );
+        assert_eq!(
+            resolve(None, None, &all[1..]),
+            ConfigPath {
+                path: PathBuf::from("/opt/win-move/config.toml"),
+                source: ConfigSource::ExecutableDir
+            }
+        );
+    }
+
+    #[test]
+    fn defaults_to_user_config_dir_when_nothing_exists() {
+        assert_eq!(
+            resolve(None, None, &[]),
+            ConfigPath {
+                path: PathBuf::from("/home/user/.config/win-move/config.toml"),
+                source: ConfigSource::NotFound
+            }
+        );
+        // Explicit paths are used even if the file is missing
+        assert_eq!(
+            resolve(None, Some("missing.toml"), &[]).source,
+            ConfigSource::Environment
+        );
+    }
+}
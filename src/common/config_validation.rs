@@ -0,0 +1,509 @@
+use crate::common::{
+    config::Config,
+    enums::{HotKeyButton, HotKeyModifier},
+    hotkey::{find_duplicate_hotkeys, Hotkey},
+    hotkey_action::HotKeyAction,
+    structs::{GridCell, GridLayout, HotkeyMapping, MonitorTarget, ResizeStep},
+};
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::ops::Range;
+use std::path::Path;
+
+/// Problem found in the configuration file
+#[derive(Debug, Clone, PartialEq)]
+pub struct Diagnostic {
+    /// Line and column in the file, both starting from 1
+    pub location: Option<(usize, usize)>,
+    pub message: String,
+}
+
+impl Diagnostic {
+    /// Formats the diagnostic prefixed with the file it's in, eg. "config.toml:3:10: message"
+    pub fn format_with_path(&self, path: &Path) -> String {
+        match self.location {
+            Some(_) => format!("{}:{}", path.display(), self),
+            None => format!("{}: {}", path.display(), self),
+        }
+    }
+}
+
+impl std::fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if let Some((line, column)) = self.location {
+            write!(f, "{}:{}: ", line, column)?;
+        }
+        write!(f, "{}", self.message)
+    }
+}
+
+/// Result of validating a configuration file
+#[derive(Debug)]
+pub struct ValidatedConfig {
+    /// The configuration without the invalid hotkeys, `None` if the file couldn't be read as a
+    /// configuration at all
+    pub config: Option<Config>,
+    pub diagnostics: Vec<Diagnostic>,
+}
+
+impl ValidatedConfig {
+    pub fn is_valid(&self) -> bool {
+        self.config.is_some() && self.diagnostics.is_empty()
+    }
+}
+
+type SpannedTable = BTreeMap<String, toml::Spanned<toml::Value>>;
+
+// Only the arrays of tables of the configuration, with the locations of the entries and their
+// fields
+#[derive(Deserialize, Default)]
+struct SpannedEntries {
+    #[serde(default)]
+    hotkeys: Vec<toml::Spanned<SpannedTable>>,
+    #[serde(default)]
+    size_cycles: Vec<toml::Spanned<SpannedTable>>,
+    #[serde(default)]
+    grids: Vec<toml::Spanned<SpannedTable>>,
+}
+
+// Location of `field` in the entry at `index`, or of the whole entry if the field isn't set
+fn field_span(
+    entries: &[toml::Spanned<SpannedTable>],
+    index: usize,
+    field: &str,
+) -> Option<Range<usize>> {
+    let entry = entries.get(index)?;
+    Some(
+        entry
+            .get_ref()
+            .get(field)
+            .map_or_else(|| entry.span(), |value| value.span()),
+    )
+}
+
+fn is_positive(step: &ResizeStep) -> bool {
+    match step {
+        ResizeStep::Pixels(pixels) => *pixels > 0,
+        ResizeStep::Fraction(fraction) => *fraction > 0.0,
+    }
+}
+
+fn fits_into(cell: &GridCell, grid: &GridLayout) -> bool {
+    cell.column + cell.column_span.max(1) <= grid.columns
+        && cell.row + cell.row_span.max(1) <= grid.rows
+}
+
+// Line and column of the byte `offset` in `contents`, both starting from 1
+fn line_and_column(contents: &str, offset: usize) -> (usize, usize) {
+    let before = &contents[..offset.min(contents.len())];
+    let line = before.matches('\n').count() + 1;
+    let line_start = before.rfind('\n').map_or(0, |index| index + 1);
+    (line, before[line_start..].chars().count() + 1)
+}
+
+struct Validator<'a> {
+    contents: &'a str,
+    diagnostics: Vec<Diagnostic>,
+}
+
+impl Validator<'_> {
+    fn report(&mut self, span: Option<Range<usize>>, message: String) {
+        self.diagnostics.push(Diagnostic {
+            location: span.map(|span| line_and_column(self.contents, span.start)),
+            // TOML errors can span several lines
+            message: message.trim().replace('\n', ", "),
+        });
+    }
+
+    // Checks a single field of a hotkey entry, so that the error points to the field
+    fn check_field<T: serde::de::DeserializeOwned>(
+        &mut self,
+        entry: &SpannedTable,
+        field: &str,
+        message: impl Fn(&toml::Value, String) -> String,
+    ) -> bool {
+        let Some(value) = entry.get(field) else {
+            return true;
+        };
+        match value.get_ref().clone().try_into::<T>() {
+            Ok(_) => true,
+            Err(error) => {
+                let message = message(value.get_ref(), error.message().to_string());
+                self.report(Some(value.span()), message);
+                false
+            }
+        }
+    }
+
+    // Validates each hotkey entry on its own. Returns the values and locations of the valid ones.
+    fn check_hotkeys(
+        &mut self,
+        hotkeys: Vec<toml::Spanned<SpannedTable>>,
+    ) -> Vec<(toml::Value, Range<usize>)> {
+        let mut valid = vec![];
+        for entry in hotkeys {
+            let span = entry.span();
+            let entry = entry.into_inner();
+            let fields_valid = [
+                self.check_field::<HotKeyAction>(&entry, "action", |_, error| error),
+                self.check_field::<Hotkey>(&entry, "hotkey", |_, error| error),
+                self.check_field::<HotKeyButton>(&entry, "key", |value, _| {
+                    format!(
+                        "unknown key {}, run `win-move list-keys` for the key names",
+                        value
+                    )
+                }),
+                self.check_field::<HotKeyModifier>(&entry, "modifier", |value, _| {
+                    format!(
+                        "unknown modifier {}, expected \"None\", \"ModControl\" or \"ModAlt\"",
+                        value
+                    )
+                }),
+            ];
+            if fields_valid.contains(&false) {
+                continue;
+            }
+
+            // Unknown and missing fields, and fields that can't be used together
+            let value = toml::Value::Table(
+                entry
+                    .into_iter()
+                    .map(|(key, value)| (key, value.into_inner()))
+                    .collect(),
+            );
+            match value.clone().try_into::<HotkeyMapping>() {
+                Ok(_) => valid.push((value, span)),
+                Err(error) => {
+                    self.report(Some(span), format!("invalid hotkey: {}", error.message()))
+                }
+            }
+        }
+        valid
+    }
+
+    fn check_references(&mut self, config: &Config, spans: &[Range<usize>]) {
+        let duplicates = find_duplicate_hotkeys(&config.hotkeys);
+        for (index, mapping) in config.hotkeys.iter().enumerate() {
+            let span = spans.get(index).cloned();
+            if let Some(previous) = duplicates[index] {
+                let previous_line = spans
+                    .get(previous)
+                    .map(|span| line_and_column(self.contents, span.start).0);
+                self.report(
+                    span.clone(),
+                    match previous_line {
+                        Some(line) => format!(
+                            "hotkey {} is already bound on line {}",
+                            mapping.hotkey, line
+                        ),
+                        None => format!("hotkey {} is bound more than once", mapping.hotkey),
+                    },
+                );
+            }
+
+            match &mapping.action {
+                HotKeyAction::MoveWindowToZone { zone }
+                    if !config.zones.iter().any(|z| z.name == *zone) =>
+                {
+                    self.report(span, format!("no zone called '{}' in [[zones]]", zone))
+                }
+                HotKeyAction::MoveWindowToMonitor(MonitorTarget::Name(name))
+                    if config.get_monitor_index_by_name(name).is_none() =>
+                {
+                    self.report(
+                        span,
+                        format!("no monitor called '{}' in [[monitors]]", name),
+                    )
+                }
+                HotKeyAction::ResizeWindow { step, .. } if !is_positive(step) => self.report(
+                    span,
+                    format!("resize step {} must be larger than zero", step),
+                ),
+                // Cells only have to fit into one of the grids, the grids of the monitors may differ
+                HotKeyAction::MoveWindowToGridCell(cell)
+                    if !std::iter::once(config.get_grid_layout(None))
+                        .chain(config.grids.iter().cloned())
+                        .any(|grid| fits_into(cell, &grid)) =>
+                {
+                    self.report(
+                        span,
+                        format!(
+                            "grid cell at column {}, row {} is outside the grid",
+                            cell.column, cell.row
+                        ),
+                    )
+                }
+                _ => (),
+            }
+        }
+    }
+
+    // Checks the values of the size cycles and grids that can't be used
+    fn check_layouts(
+        &mut self,
+        config: &Config,
+        size_cycles: &[toml::Spanned<SpannedTable>],
+        grids: &[toml::Spanned<SpannedTable>],
+    ) {
+        for (index, cycle) in config.size_cycles.iter().enumerate() {
+            let span = field_span(size_cycles, index, "fractions");
+            if cycle.fractions.is_empty() {
+                self.report(span, "size cycle has no fractions".to_string());
+            } else if let Some(fraction) = cycle
+                .fractions
+                .iter()
+                .find(|fraction| !(**fraction > 0.0 && **fraction <= 1.0))
+            {
+                self.report(
+                    span,
+                    format!(
+                        "size cycle fraction {} must be larger than 0 and at most 1",
+                        fraction
+                    ),
+                );
+            }
+        }
+
+        for (index, grid) in config.grids.iter().enumerate() {
+            for (field, value) in [("columns", grid.columns), ("rows", grid.rows)] {
+                if value == 0 {
+                    self.report(
+                        field_span(grids, index, field),
+                        format!("grid {} must be at least 1", field),
+                    );
+                }
+            }
+        }
+    }
+}
+
+/// Validates the contents of a configuration file. Reports TOML syntax errors, invalid hotkeys
+/// (unknown actions, keys and parameters), duplicate hotkeys, references to zones and monitors
+/// missing from the configuration, grid cells outside the grids and unusable resize steps, size
+/// cycles and grids.
+pub fn validate_config(contents: &str) -> ValidatedConfig {
+    let mut validator = Validator {
+        contents,
+        diagnostics: vec![],
+    };
+
+    let mut table = match contents.parse::<toml::Table>() {
+        Ok(table) => table,
+        Err(error) => {
+            validator.report(error.span(), error.message().to_string());
+            return ValidatedConfig {
+                config: None,
+                diagnostics: validator.diagnostics,
+            };
+        }
+    };
+
+    let entries = match toml::from_str::<SpannedEntries>(contents) {
+        Ok(entries) => entries,
+        Err(error) => {
+            validator.report(error.span(), error.message().to_string());
+            SpannedEntries::default()
+        }
+    };
+    let hotkey_count = entries.hotkeys.len();
+    let valid_hotkeys = validator.check_hotkeys(entries.hotkeys);
+
+    let config = if validator.diagnostics.is_empty() {
+        // Parsing the file itself gives the locations of the errors in the rest of the file
+        toml::from_str::<Config>(contents)
+            .map_err(|error| (error.span(), error.message().to_string()))
+    } else {
+        // Only the valid hotkeys are kept, so a typo in one hotkey doesn't disable all of them
+        if table.contains_key("hotkeys") || hotkey_count > 0 {
+            table.insert(
+                "hotkeys".to_string(),
+                toml::Value::Array(
+                    valid_hotkeys
+                        .iter()
+                        .map(|(value, _)| value.clone())
+                        .collect(),
+                ),
+            );
+        }
+        toml::Value::Table(table)
+            .try_into::<Config>()
+            .map_err(|error| (None, error.message().to_string()))
+    };
+
+    let config = match config {
+        Ok(config) => {
+            let spans: Vec<Range<usize>> =
+                valid_hotkeys.into_iter().map(|(_, span)| span).collect();
+            validator.check_references(&config, &spans);
+            validator.check_layouts(&config, &entries.size_cycles, &entries.grids);
+            Some(config)
+        }
+        Err((span, message)) => {
+            validator.report(span, message);
+            None
+        }
+    };
+
+    ValidatedConfig {
+        config,
+        diagnostics: validator.diagnostics,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn messages(contents: &str) -> Vec<String> {
+        validate_config(contents)
+            .diagnostics
+            .iter()
+            .map(|diagnostic| diagnostic.to_string())
+            .collect()
+    }
+
+    #[test]
+    fn valid_config_has_no_diagnostics() {
+        let validated = validate_config(include_str!("../../config.toml.example"));
+        assert!(validated.is_valid(), "{:?}", validated.diagnostics);
+    }
+
+    #[test]
+    fn reports_syntax_errors_with_location() {
+        assert_eq!(
+            messages("inner_gap = 4\nouter_padding = \n"),
+            vec!["2:17: invalid string, expected `\"`, `'`"]
+        );
+    }
+
+    #[test]
+    fn reports_every_invalid_hotkey_and_keeps_the_valid_ones() {
+        let validated = validate_config(
+            r#"
+[[hotkeys]]
+action = "MoveWindowToLeftMidle"
+hotkey = "Ctrl+Numpad4"
+
+[[hotkeys]]
+action = "MoveWindowToRightMiddle"
+hotkey = "Ctrl+Rigth"
+
+[[hotkeys]]
+action = "MoveWindowToTop"
+key = "VkNumpad88"
+modifier = "ModControl"
+
+[[hotkeys]]
+action = { type = "MoveToMonitor", index = 1, name = "left" }
+hotkey = "Alt+1"
+
+[[hotkeys]]
+action = "MaximizeWindow"
+hotkey = "Ctrl+Numpad5"
+"#,
+        );
+        let messages: Vec<String> = validated
+            .diagnostics
+            .iter()
+            .map(|diagnostic| diagnostic.to_string())
+            .collect();
+        assert_eq!(
+            messages,
+            vec![
+                "3:10: unknown action 'MoveWindowToLeftMidle'",
+                "8:10: unknown key 'Rigth' in hotkey 'Ctrl+Rigth', run `win-move list-keys` for the key names",
+                "12:7: unknown key \"VkNumpad88\", run `win-move list-keys` for the key names",
+                "16:10: MoveToMonitor: takes either 'index' or 'name'",
+            ]
+        );
+        let config = validated.config.unwrap();
+        assert_eq!(config.hotkeys.len(), 1);
+        assert_eq!(config.hotkeys[0].action, HotKeyAction::MaximizeWindow);
+    }
+
+    #[test]
+    fn reports_conflicting_hotkey_fields() {
+        assert_eq!(
+            messages(
+                r#"
+[[hotkeys]]
+action = "MaximizeWindow"
+hotkey = "Ctrl+Numpad5"
+key = "VkNumpad5"
+"#
+            ),
+            vec!["2:1: invalid hotkey: 'hotkey' can't be combined with 'key' or 'modifier'"]
+        );
+    }
+
+    #[test]
+    fn reports_duplicates_and_missing_references() {
+        assert_eq!(
+            messages(
+                r#"
+[[hotkeys]]
+action = "MoveWindowToLeftMiddle"
+hotkey = "Ctrl+Numpad4"
+
+[[hotkeys]]
+action = { type = "MoveToZone", zone = "main" }
+key = "VkNumpad4"
+modifier = "ModControl"
+
+[[hotkeys]]
+action = { type = "MoveToMonitor", name = "left" }
+hotkey = "Alt+1"
+"#
+            ),
+            vec![
+                "6:1: hotkey Ctrl+Numpad4 is already bound on line 2",
+                "6:1: no zone called 'main' in [[zones]]",
+                "11:1: no monitor called 'left' in [[monitors]]",
+            ]
+        );
+    }
+
+    #[test]
+    fn reports_errors_outside_hotkeys() {
+        assert_eq!(
+            messages("inner_gap = \"wide\"\nhotkeys = []\n"),
+            vec!["1:13: invalid type: string \"wide\", expected i32"]
+        );
+    }
+
+    #[test]
+    fn reports_unusable_sizes_with_location() {
+        assert_eq!(
+            messages(
+                r#"
+[[hotkeys]]
+action = { type = "Resize", direction = "Left", step = "0%" }
+hotkey = "Ctrl+Alt+Left"
+
+[[hotkeys]]
+action = { type = "MoveToGridCell", column = 2, row = 0 }
+hotkey = "Ctrl+Alt+G"
+
+[[size_cycles]]
+action = "MoveWindowToLeftMiddle"
+fractions = []
+
+[[size_cycles]]
+action = "MoveWindowToRightMiddle"
+fractions = [0.5, 1.5]
+
+[[grids]]
+columns = 0
+rows = 2
+"#
+            ),
+            vec![
+                "2:1: resize step 0% must be larger than zero",
+                "6:1: grid cell at column 2, row 0 is outside the grid",
+                "12:13: size cycle has no fractions",
+                "16:13: size cycle fraction 1.5 must be larger than 0 and at most 1",
+                "19:11: grid columns must be at least 1",
+            ]
+        );
+    }
+}
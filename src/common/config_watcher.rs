@@ -0,0 +1,79 @@
+use std::fs;
+use std::path::PathBuf;
+use std::thread;
+use std::time::{Duration, SystemTime};
+
+// How often the configuration file is checked for changes
+const POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Detects changes to a file by comparing its modification time between calls
+pub struct FileChangeDetector {
+    path: PathBuf,
+    last_modified: Option<SystemTime>,
+}
+
+impl FileChangeDetector {
+    pub fn new(path: PathBuf) -> Self {
+        let last_modified = get_modified_time(&path);
+        FileChangeDetector {
+            path,
+            last_modified,
+        }
+    }
+
+    /// Returns true if the file was modified, created or removed since the previous call
+    pub fn has_changed(&mut self) -> bool {
+        let modified = get_modified_time(&self.path);
+        if modified == self.last_modified {
+            return false;
+        }
+        self.last_modified = modified;
+        true
+    }
+}
+
+fn get_modified_time(path: &PathBuf) -> Option<SystemTime> {
+    fs::metadata(path)
+        .and_then(|metadata| metadata.modified())
+        .ok()
+}
+
+/// Watches the configuration file in a background thread, calling `on_change` whenever it changes
+pub fn watch_config_file(path: PathBuf, on_change: Box<dyn Fn() + Send>) {
+    let mut detector = FileChangeDetector::new(path);
+    thread::spawn(move || loop {
+        thread::sleep(POLL_INTERVAL);
+        if detector.has_changed() {
+            log::debug!("Configuration file {:?} changed", detector.path);
+            on_change();
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn detects_modification_and_removal() {
+        let path = std::env::temp_dir().join(format!("win-move-watch-{}.toml", std::process::id()));
+        let _ = fs::remove_file(&path);
+
+        let mut detector = FileChangeDetector::new(path.clone());
+        assert!(!detector.has_changed());
+
+        fs::write(&path, "hotkeys = []").unwrap();
+        assert!(detector.has_changed());
+        assert!(!detector.has_changed());
+
+        // Make sure the modification time differs even on file systems with coarse timestamps
+        let file = fs::File::options().write(true).open(&path).unwrap();
+        file.set_modified(SystemTime::now() + Duration::from_secs(10))
+            .unwrap();
+        assert!(detector.has_changed());
+
+        fs::remove_file(&path).unwrap();
+        assert!(detector.has_changed());
+        assert!(!detector.has_changed());
+    }
+}
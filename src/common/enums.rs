@@ -1,6 +1,49 @@
-/// Enums for buttons
-#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+use crate::common::hotkey_action::HotKeyAction;
+
+/// Keys hotkeys can be bound to. Each backend maps these to its native key codes.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
 pub enum HotKeyButton {
+    // Letters
+    VkA,
+    VkB,
+    VkC,
+    VkD,
+    VkE,
+    VkF,
+    VkG,
+    VkH,
+    VkI,
+    VkJ,
+    VkK,
+    VkL,
+    VkM,
+    VkN,
+    VkO,
+    VkP,
+    VkQ,
+    VkR,
+    VkS,
+    VkT,
+    VkU,
+    VkV,
+    VkW,
+    VkX,
+    VkY,
+    VkZ,
+
+    // Digits above the letters
+    Vk0,
+    Vk1,
+    Vk2,
+    Vk3,
+    Vk4,
+    Vk5,
+    Vk6,
+    Vk7,
+    Vk8,
+    Vk9,
+
+    // Numpad
     VkNumpad0,
     VkNumpad1,
     VkNumpad2,
@@ -12,9 +55,185 @@ pub enum HotKeyButton {
     VkNumpad8,
     VkNumpad9,
     VkDecimal,
+    VkMultiply,
+    VkAdd,
+    VkSubtract,
+    VkDivide,
+
+    // Function keys
+    VkF1,
+    VkF2,
+    VkF3,
+    VkF4,
+    VkF5,
+    VkF6,
+    VkF7,
+    VkF8,
+    VkF9,
+    VkF10,
+    VkF11,
+    VkF12,
+    VkF13,
+    VkF14,
+    VkF15,
+    VkF16,
+    VkF17,
+    VkF18,
+    VkF19,
+    VkF20,
+    VkF21,
+    VkF22,
+    VkF23,
+    VkF24,
+
+    // Arrows
+    VkLeft,
+    VkRight,
+    VkUp,
+    VkDown,
+
+    // Navigation and editing
+    VkHome,
+    VkEnd,
+    VkPageUp,
+    VkPageDown,
+    VkInsert,
+    VkDelete,
+    VkBackspace,
+    VkTab,
+    VkEnter,
+    VkEscape,
+    VkSpace,
+
+    // Punctuation, named after the keys of a US keyboard layout
+    VkComma,
+    VkPeriod,
+    VkMinus,
+    VkEquals,
+    VkSemicolon,
+    VkSlash,
+    VkBackquote,
+    VkLeftBracket,
+    VkBackslash,
+    VkRightBracket,
+    VkQuote,
 }
 
-/// Enums for modifiers
+impl HotKeyButton {
+    /// All keys, in the order they're declared
+    pub const ALL: [HotKeyButton; 101] = [
+        HotKeyButton::VkA,
+        HotKeyButton::VkB,
+        HotKeyButton::VkC,
+        HotKeyButton::VkD,
+        HotKeyButton::VkE,
+        HotKeyButton::VkF,
+        HotKeyButton::VkG,
+        HotKeyButton::VkH,
+        HotKeyButton::VkI,
+        HotKeyButton::VkJ,
+        HotKeyButton::VkK,
+        HotKeyButton::VkL,
+        HotKeyButton::VkM,
+        HotKeyButton::VkN,
+        HotKeyButton::VkO,
+        HotKeyButton::VkP,
+        HotKeyButton::VkQ,
+        HotKeyButton::VkR,
+        HotKeyButton::VkS,
+        HotKeyButton::VkT,
+        HotKeyButton::VkU,
+        HotKeyButton::VkV,
+        HotKeyButton::VkW,
+        HotKeyButton::VkX,
+        HotKeyButton::VkY,
+        HotKeyButton::VkZ,
+        HotKeyButton::Vk0,
+        HotKeyButton::Vk1,
+        HotKeyButton::Vk2,
+        HotKeyButton::Vk3,
+        HotKeyButton::Vk4,
+        HotKeyButton::Vk5,
+        HotKeyButton::Vk6,
+        HotKeyButton::Vk7,
+        HotKeyButton::Vk8,
+        HotKeyButton::Vk9,
+        HotKeyButton::VkNumpad0,
+        HotKeyButton::VkNumpad1,
+        HotKeyButton::VkNumpad2,
+        HotKeyButton::VkNumpad3,
+        HotKeyButton::VkNumpad4,
+        HotKeyButton::VkNumpad5,
+        HotKeyButton::VkNumpad6,
+        HotKeyButton::VkNumpad7,
+        HotKeyButton::VkNumpad8,
+        HotKeyButton::VkNumpad9,
+        HotKeyButton::VkDecimal,
+        HotKeyButton::VkMultiply,
+        HotKeyButton::VkAdd,
+        HotKeyButton::VkSubtract,
+        HotKeyButton::VkDivide,
+        HotKeyButton::VkF1,
+        HotKeyButton::VkF2,
+        HotKeyButton::VkF3,
+        HotKeyButton::VkF4,
+        HotKeyButton::VkF5,
+        HotKeyButton::VkF6,
+        HotKeyButton::VkF7,
+        HotKeyButton::VkF8,
+        HotKeyButton::VkF9,
+        HotKeyButton::VkF10,
+        HotKeyButton::VkF11,
+        HotKeyButton::VkF12,
+        HotKeyButton::VkF13,
+        HotKeyButton::VkF14,
+        HotKeyButton::VkF15,
+        HotKeyButton::VkF16,
+        HotKeyButton::VkF17,
+        HotKeyButton::VkF18,
+        HotKeyButton::VkF19,
+        HotKeyButton::VkF20,
+        HotKeyButton::VkF21,
+        HotKeyButton::VkF22,
+        HotKeyButton::VkF23,
+        HotKeyButton::VkF24,
+        HotKeyButton::VkLeft,
+        HotKeyButton::VkRight,
+        HotKeyButton::VkUp,
+        HotKeyButton::VkDown,
+        HotKeyButton::VkHome,
+        HotKeyButton::VkEnd,
+        HotKeyButton::VkPageUp,
+        HotKeyButton::VkPageDown,
+        HotKeyButton::VkInsert,
+        HotKeyButton::VkDelete,
+        HotKeyButton::VkBackspace,
+        HotKeyButton::VkTab,
+        HotKeyButton::VkEnter,
+        HotKeyButton::VkEscape,
+        HotKeyButton::VkSpace,
+        HotKeyButton::VkComma,
+        HotKeyButton::VkPeriod,
+        HotKeyButton::VkMinus,
+        HotKeyButton::VkEquals,
+        HotKeyButton::VkSemicolon,
+        HotKeyButton::VkSlash,
+        HotKeyButton::VkBackquote,
+        HotKeyButton::VkLeftBracket,
+        HotKeyButton::VkBackslash,
+        HotKeyButton::VkRightBracket,
+        HotKeyButton::VkQuote,
+    ];
+
+    /// Name of the key as written in hotkey strings, eg. "Numpad7" or "Left"
+    pub fn name(&self) -> String {
+        // The variants are named after the keys with a "Vk" prefix
+        let name = format!("{:?}", self);
+        name.strip_prefix("Vk").unwrap_or(&name).to_string()
+    }
+}
+
+/// Enums for modifiers, used by the `modifier` field of hotkeys written with `key`
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub enum HotKeyModifier {
     None,
@@ -22,10 +241,44 @@ pub enum HotKeyModifier {
     ModAlt,
 }
 
-#[derive(Debug, PartialEq)]
+/// Order in which monitors are numbered in the configuration
+#[derive(Debug, Copy, Clone, PartialEq, Default, serde::Serialize, serde::Deserialize)]
+pub enum MonitorOrder {
+    #[default]
+    LeftToRight,
+    TopToBottom,
+}
+
+/// Events the main loop receives from the hotkey handler
+#[derive(Debug, Clone, PartialEq)]
+pub enum HandlerEvent {
+    /// A registered hotkey was pressed
+    Hotkey(HotKeyAction),
+    /// The configuration file changed and should be reloaded
+    ConfigChanged,
+    /// The process should exit, eg. after Ctrl+C
+    Quit,
+    /// A message the main loop doesn't act on, such as a hotkey that isn't registered
+    Ignored,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
 pub enum WindowState {
     Other = 0,
     Normal = 1,
     Minimized = 2,
     Maximized = 3,
 }
+
+/// Edge or corner of the monitor a window is resized towards
+#[derive(Debug, Copy, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub enum ResizeDirection {
+    LeftBottom,
+    Bottom,
+    RightBottom,
+    Left,
+    Right,
+    LeftTop,
+    Top,
+    RightTop,
+}
@@ -0,0 +1,362 @@
+use crate::common::enums::{HotKeyButton, HotKeyModifier};
+use crate::common::structs::HotkeyMapping;
+
+/// Set of modifier keys held down with a hotkey
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Default)]
+pub struct HotKeyModifiers {
+    pub control: bool,
+    pub alt: bool,
+    pub shift: bool,
+    /// The Windows key, Super on Linux
+    pub super_key: bool,
+}
+
+impl HotKeyModifiers {
+    pub const NONE: HotKeyModifiers = HotKeyModifiers {
+        control: false,
+        alt: false,
+        shift: false,
+        super_key: false,
+    };
+    pub const CONTROL: HotKeyModifiers = HotKeyModifiers {
+        control: true,
+        ..HotKeyModifiers::NONE
+    };
+    pub const ALT: HotKeyModifiers = HotKeyModifiers {
+        alt: true,
+        ..HotKeyModifiers::NONE
+    };
+}
+
+impl From<HotKeyModifier> for HotKeyModifiers {
+    fn from(modifier: HotKeyModifier) -> Self {
+        match modifier {
+            HotKeyModifier::None => HotKeyModifiers::NONE,
+            HotKeyModifier::ModControl => HotKeyModifiers::CONTROL,
+            HotKeyModifier::ModAlt => HotKeyModifiers::ALT,
+        }
+    }
+}
+
+/// Key combination triggering an action, written as a string like `"Ctrl+Alt+Left"`
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct Hotkey {
+    pub key: HotKeyButton,
+    pub modifiers: HotKeyModifiers,
+}
+
+// Other names the keys can be written with, all in lowercase. The "KP_" names of the numpad keys
+// are handled separately.
+const KEY_ALIASES: [(&str, &str); 21] = [
+    ("esc", "escape"),
+    ("return", "enter"),
+    ("del", "delete"),
+    ("ins", "insert"),
+    ("pgup", "pageup"),
+    ("prior", "pageup"),
+    ("pgdn", "pagedown"),
+    ("next", "pagedown"),
+    ("back", "backspace"),
+    ("grave", "backquote"),
+    (",", "comma"),
+    (".", "period"),
+    ("-", "minus"),
+    ("=", "equals"),
+    (";", "semicolon"),
+    ("/", "slash"),
+    ("`", "backquote"),
+    ("[", "leftbracket"),
+    ("\\", "backslash"),
+    ("]", "rightbracket"),
+    ("'", "quote"),
+];
+
+fn parse_modifier(name: &str) -> Option<fn(&mut HotKeyModifiers) -> &mut bool> {
+    match name.to_ascii_lowercase().as_str() {
+        "ctrl" | "control" => Some(|modifiers| &mut modifiers.control),
+        "alt" => Some(|modifiers| &mut modifiers.alt),
+        "shift" => Some(|modifiers| &mut modifiers.shift),
+        "super" | "win" | "meta" => Some(|modifiers| &mut modifiers.super_key),
+        _ => None,
+    }
+}
+
+/// Parses a key name such as "Left", "F11", "Numpad7", "KP_7" or "[". Case insensitive.
+pub fn parse_key(name: &str) -> Option<HotKeyButton> {
+    let lowercase = name.to_ascii_lowercase();
+    let mut normalized = match lowercase.strip_prefix("kp_") {
+        Some(digit) if digit.len() == 1 && digit.as_bytes()[0].is_ascii_digit() => {
+            format!("numpad{}", digit)
+        }
+        Some(numpad_key) => numpad_key.to_string(),
+        None => lowercase,
+    };
+    if let Some((_, key)) = KEY_ALIASES.iter().find(|(alias, _)| *alias == normalized) {
+        normalized = key.to_string();
+    }
+    let normalized = normalized
+        .strip_prefix("vk")
+        .filter(|key| !key.is_empty())
+        .unwrap_or(&normalized);
+
+    HotKeyButton::ALL
+        .iter()
+        .find(|key| key.name().to_ascii_lowercase() == normalized)
+        .copied()
+}
+
+/// Names of all keys, one per line
+pub fn format_key_list() -> String {
+    HotKeyButton::ALL
+        .iter()
+        .map(|key| key.name() + "\n")
+        .collect()
+}
+
+impl std::str::FromStr for Hotkey {
+    type Err = String;
+
+    fn from_str(hotkey: &str) -> Result<Self, Self::Err> {
+        let parts: Vec<&str> = hotkey.split('+').map(str::trim).collect();
+        let (key, modifier_names) = parts.split_last().unwrap_or((&"", &[]));
+        if key.is_empty() {
+            return Err(format!("missing key in hotkey '{}'", hotkey));
+        }
+
+        let mut modifiers = HotKeyModifiers::NONE;
+        for name in modifier_names {
+            let modifier = match parse_modifier(name) {
+                Some(modifier) => modifier,
+                None if parse_key(name).is_some() => {
+                    return Err(format!(
+                        "'{}' in hotkey '{}' is a key, only the last part of a hotkey can be a key",
+                        name, hotkey
+                    ))
+                }
+                None => {
+                    return Err(format!(
+                        "unknown modifier '{}' in hotkey '{}', expected Ctrl, Alt, Shift or Super",
+                        name, hotkey
+                    ))
+                }
+            };
+            if std::mem::replace(modifier(&mut modifiers), true) {
+                return Err(format!(
+                    "modifier '{}' repeated in hotkey '{}'",
+                    name, hotkey
+                ));
+            }
+        }
+
+        let key = parse_key(key).ok_or_else(|| {
+            if parse_modifier(key).is_some() {
+                format!("missing key after the modifiers in hotkey '{}'", hotkey)
+            } else {
+                format!(
+                    "unknown key '{}' in hotkey '{}', run `win-move list-keys` for the key names",
+                    key, hotkey
+                )
+            }
+        })?;
+        Ok(Hotkey { key, modifiers })
+    }
+}
+
+impl std::fmt::Display for Hotkey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (held, name) in [
+            (self.modifiers.control, "Ctrl"),
+            (self.modifiers.alt, "Alt"),
+            (self.modifiers.shift, "Shift"),
+            (self.modifiers.super_key, "Super"),
+        ] {
+            if held {
+                write!(f, "{}+", name)?;
+            }
+        }
+        write!(f, "{}", self.key.name())
+    }
+}
+
+impl serde::Serialize for Hotkey {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.to_string())
+    }
+}
+
+impl<'de> serde::Deserialize<'de> for Hotkey {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let hotkey = String::deserialize(deserializer)?;
+        hotkey.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+/// Outcome of registering a single hotkey mapping
+#[derive(Debug, Clone, PartialEq)]
+pub enum HotkeyRegistrationStatus {
+    Registered,
+    /// The same hotkey is bound earlier in the configuration, at the given index
+    Duplicate {
+        previous: usize,
+    },
+    /// The system refused the hotkey, usually because another application already uses it
+    Failed(String),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct HotkeyRegistration {
+    pub mapping: HotkeyMapping,
+    pub status: HotkeyRegistrationStatus,
+}
+
+/// For each mapping, the index of an earlier mapping bound to the same hotkey
+pub fn find_duplicate_hotkeys(mappings: &[HotkeyMapping]) -> Vec<Option<usize>> {
+    mappings
+        .iter()
+        .enumerate()
+        .map(|(index, mapping)| {
+            mappings[..index]
+                .iter()
+                .position(|previous| previous.hotkey == mapping.hotkey)
+        })
+        .collect()
+}
+
+/// Logs the hotkeys that couldn't be registered and a summary. Returns the number of failed
+/// registrations.
+pub fn log_hotkey_registrations(registrations: &[HotkeyRegistration]) -> usize {
+    let mut failed = 0;
+    for registration in registrations {
+        let mapping = &registration.mapping;
+        match &registration.status {
+            HotkeyRegistrationStatus::Registered => continue,
+            HotkeyRegistrationStatus::Duplicate { previous } => log::warn!(
+                "Hotkey {} for {:?} is ignored, it's already bound to {:?} in the configuration",
+                mapping.hotkey,
+                mapping.action,
+                registrations[*previous].mapping.action
+            ),
+            HotkeyRegistrationStatus::Failed(reason) => log::warn!(
+                "Hotkey {} for {:?} couldn't be registered: {}",
+                mapping.hotkey,
+                mapping.action,
+                reason
+            ),
+        }
+        failed += 1;
+    }
+
+    if failed > 0 {
+        log::warn!(
+            "{} of {} hotkeys couldn't be registered",
+            failed,
+            registrations.len()
+        );
+    } else {
+        log::info!("Registered {} hotkeys", registrations.len());
+    }
+    failed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::hotkey_action::HotKeyAction;
+
+    #[test]
+    fn parses_hotkey_strings() {
+        assert_eq!(
+            "Ctrl+Alt+Left".parse(),
+            Ok(Hotkey {
+                key: HotKeyButton::VkLeft,
+                modifiers: HotKeyModifiers {
+                    control: true,
+                    alt: true,
+                    ..HotKeyModifiers::NONE
+                },
+            })
+        );
+        assert_eq!(
+            "Super+Shift+KP_7".parse(),
+            Ok(Hotkey {
+                key: HotKeyButton::VkNumpad7,
+                modifiers: HotKeyModifiers {
+                    shift: true,
+                    super_key: true,
+                    ..HotKeyModifiers::NONE
+                },
+            })
+        );
+        assert_eq!(
+            "ctrl + pgup".parse::<Hotkey>().map(|hotkey| hotkey.key),
+            Ok(HotKeyButton::VkPageUp)
+        );
+        assert_eq!(
+            "F11".parse(),
+            Ok(Hotkey {
+                key: HotKeyButton::VkF11,
+                modifiers: HotKeyModifiers::NONE,
+            })
+        );
+        assert_eq!(
+            "Alt+[".parse::<Hotkey>().map(|hotkey| hotkey.key),
+            Ok(HotKeyButton::VkLeftBracket)
+        );
+        assert_eq!(
+            "Ctrl+VkNumpad4".parse::<Hotkey>().map(|hotkey| hotkey.key),
+            Ok(HotKeyButton::VkNumpad4)
+        );
+    }
+
+    #[test]
+    fn formats_back_to_the_same_hotkey() {
+        for key in HotKeyButton::ALL {
+            let hotkey = Hotkey {
+                key,
+                modifiers: HotKeyModifiers::CONTROL,
+            };
+            assert_eq!(hotkey.to_string().parse(), Ok(hotkey));
+        }
+    }
+
+    #[test]
+    fn finds_duplicate_hotkeys() {
+        let mapping = |action, hotkey: &str| HotkeyMapping {
+            action,
+            hotkey: hotkey.parse().unwrap(),
+        };
+        let mappings = [
+            mapping(HotKeyAction::MoveWindowToLeftMiddle, "Ctrl+Numpad4"),
+            mapping(HotKeyAction::MoveWindowToLeftMiddle, "Ctrl+Alt+Left"),
+            mapping(HotKeyAction::MaximizeWindow, "ctrl+kp_4"),
+            mapping(HotKeyAction::MinimizeWindow, "Alt+Left"),
+        ];
+        assert_eq!(
+            find_duplicate_hotkeys(&mappings),
+            vec![None, None, Some(0), None]
+        );
+    }
+
+    #[test]
+    fn reports_typos() {
+        assert_eq!(
+            "Ctrl+Lfet".parse::<Hotkey>(),
+            Err(
+                "unknown key 'Lfet' in hotkey 'Ctrl+Lfet', run `win-move list-keys` for the key names"
+                    .to_string()
+            )
+        );
+        assert_eq!(
+            "Crtl+Left".parse::<Hotkey>(),
+            Err(
+                "unknown modifier 'Crtl' in hotkey 'Crtl+Left', expected Ctrl, Alt, Shift or Super"
+                    .to_string()
+            )
+        );
+        assert!("Left+Right".parse::<Hotkey>().is_err());
+        assert!("Ctrl+Alt".parse::<Hotkey>().is_err());
+        assert!("Ctrl+Ctrl+A".parse::<Hotkey>().is_err());
+        assert!("Ctrl+".parse::<Hotkey>().is_err());
+        assert!("".parse::<Hotkey>().is_err());
+    }
+}
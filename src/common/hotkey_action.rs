@@ -1,44 +1,180 @@
-extern crate num;
+use crate::common::{
+    action_registry,
+    enums::ResizeDirection,
+    structs::{GridCell, MonitorTarget, ResizeStep},
+};
+use serde::{de, Deserialize, Serialize};
 
-enum_from_primitive! {
-#[derive(Debug, Copy, Clone, PartialOrd, PartialEq, serde::Serialize, serde::Deserialize)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum HotKeyAction {
     // Move window to specified location
-    MoveWindowToLeftBottom = 1001,
-    MoveWindowToBottom = 1002,
-    MoveWindowToRightBottom = 1003,
-    MoveWindowToLeftMiddle = 1004,
-    MoveWindowToRightMiddle = 1006,
-    MoveWindowToLeftTop = 1007,
-    MoveWindowToTop = 1008,
-    MoveWindowToRightTop = 1009,
+    MoveWindowToLeftBottom,
+    MoveWindowToBottom,
+    MoveWindowToRightBottom,
+    MoveWindowToLeftMiddle,
+    MoveWindowToRightMiddle,
+    MoveWindowToLeftTop,
+    MoveWindowToTop,
+    MoveWindowToRightTop,
+
+    // Move window to a cell or a span of cells of the monitor's grid layout
+    MoveWindowToGridCell(GridCell),
+
+    // Move window to a zone defined in the configuration
+    MoveWindowToZone {
+        zone: String,
+    },
 
     // Misc actions
-    MinimizeWindow = 2001,
-    MaximizeWindow = 2002,
-    MoveWindowToLeftScreen = 2003,
-    MoveWindowToRightScreen = 2004,
-    MoveWindowToLeftScreenContinuous = 2005,
-    MoveWindowToRightScreenContinuous = 2006,
-
-    // Change window size
-    ChangeWindowSizeTowardsLeftBottom = 4001,
-    ChangeWindowSizeTowardsBottom = 4002,
-    ChangeWindowSizeTowardsRightBottom = 4003,
-    ChangeWindowSizeTowardsLeftMiddle = 4004,
-    ChangeWindowSizeTowardsRightMiddle = 4005,
-    ChangeWindowSizeTowardsLeftTop = 4006,
-    ChangeWindowSizeTowardsTop = 4007,
-    ChangeWindowSizeTowardsRightTop = 4008,
-
-    // Change focused window size, decrease others
-    ChangeWindowSizeTowardsLeftBottomHistoryAware = 5001,
-    ChangeWindowSizeTowardsBottomHistoryAware = 5002,
-    ChangeWindowSizeTowardsRightBottomHistoryAware = 5003,
-    ChangeWindowSizeTowardsLeftMiddleHistoryAware = 5004,
-    ChangeWindowSizeTowardsRightMiddleHistoryAware = 5005,
-    ChangeWindowSizeTowardsLeftTopHistoryAware = 5006,
-    ChangeWindowSizeTowardsTopHistoryAware = 5007,
-    ChangeWindowSizeTowardsRightTopHistoryAware = 5008,
+    MinimizeWindow,
+    MaximizeWindow,
+    MoveWindowToLeftScreen,
+    MoveWindowToRightScreen,
+    MoveWindowToLeftScreenContinuous,
+    MoveWindowToRightScreenContinuous,
+    MoveWindowToUpperScreen,
+    MoveWindowToLowerScreen,
+    MoveWindowToMonitor(MonitorTarget),
+
+    // Change window size. The history aware variant also decreases/increases the windows next to it.
+    ResizeWindow {
+        direction: ResizeDirection,
+        step: ResizeStep,
+        history_aware: bool,
+    },
+}
+
+// Actions without parameters are written by name: `action = "MoveWindowToTop"`. Actions taking
+// parameters are written as a table with the action's `type`:
+// `action = { type = "Resize", direction = "Left", step = "5%" }`
+impl Serialize for HotKeyAction {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let descriptor = action_registry::find_descriptor_of(self)
+            .ok_or_else(|| serde::ser::Error::custom(format!("unregistered action {:?}", self)))?;
+        if descriptor.parameters.is_empty() {
+            return serializer.serialize_str(descriptor.name);
+        }
+
+        let mut table = descriptor.parameters_of(self).unwrap_or_default();
+        table.insert(
+            "type".to_string(),
+            toml::Value::String(descriptor.name.to_string()),
+        );
+        table.serialize(serializer)
+    }
 }
+
+impl<'de> Deserialize<'de> for HotKeyAction {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct ActionVisitor;
+
+        impl<'de> de::Visitor<'de> for ActionVisitor {
+            type Value = HotKeyAction;
+
+            fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+                formatter.write_str("an action name or a table with the action's type")
+            }
+
+            fn visit_str<E: de::Error>(self, value: &str) -> Result<Self::Value, E> {
+                action_registry::parse_action(value, toml::Table::new()).map_err(E::custom)
+            }
+
+            fn visit_map<A: de::MapAccess<'de>>(self, map: A) -> Result<Self::Value, A::Error> {
+                let mut parameters =
+                    toml::Table::deserialize(de::value::MapAccessDeserializer::new(map))?;
+                let name = match parameters.remove("type") {
+                    Some(toml::Value::String(name)) => name,
+                    Some(_) => return Err(de::Error::custom("action 'type' must be a string")),
+                    None => return Err(de::Error::missing_field("type")),
+                };
+                action_registry::parse_action(&name, parameters).map_err(de::Error::custom)
+            }
+        }
+
+        deserializer.deserialize_any(ActionVisitor)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Serialize, Deserialize)]
+    struct Mapping {
+        action: HotKeyAction,
+    }
+
+    fn parse(action: &str) -> Result<HotKeyAction, toml::de::Error> {
+        toml::from_str::<Mapping>(&format!("action = {}", action)).map(|mapping| mapping.action)
+    }
+
+    #[test]
+    fn action_names_are_still_accepted() {
+        assert_eq!(
+            parse(r#""MoveWindowToTop""#).unwrap(),
+            HotKeyAction::MoveWindowToTop
+        );
+        assert_eq!(
+            parse(r#""ChangeWindowSizeTowardsLeftMiddleHistoryAware""#).unwrap(),
+            HotKeyAction::ResizeWindow {
+                direction: ResizeDirection::Left,
+                step: ResizeStep::Fraction(0.1),
+                history_aware: true,
+            }
+        );
+        assert!(parse(r#""MoveWindowToNowhere""#).is_err());
+    }
+
+    #[test]
+    fn tagged_actions() {
+        assert_eq!(
+            parse(r#"{ type = "Resize", direction = "Left", step = "5%" }"#).unwrap(),
+            HotKeyAction::ResizeWindow {
+                direction: ResizeDirection::Left,
+                step: ResizeStep::Fraction(0.05),
+                history_aware: false,
+            }
+        );
+        assert_eq!(
+            parse(
+                r#"{ type = "Resize", direction = "RightTop", step = 40, history_aware = true }"#
+            )
+            .unwrap(),
+            HotKeyAction::ResizeWindow {
+                direction: ResizeDirection::RightTop,
+                step: ResizeStep::Pixels(40),
+                history_aware: true,
+            }
+        );
+        assert_eq!(
+            parse(r#"{ type = "MoveToMonitor", index = 2 }"#).unwrap(),
+            HotKeyAction::MoveWindowToMonitor(MonitorTarget::Index(2))
+        );
+        assert!(parse(r#"{ type = "MoveToMonitor", index = 2, name = "left" }"#).is_err());
+        assert!(parse(r#"{ type = "Teleport" }"#).is_err());
+    }
+
+    #[test]
+    fn serialized_actions_deserialize_back() {
+        for action in [
+            HotKeyAction::MaximizeWindow,
+            HotKeyAction::MoveWindowToZone {
+                zone: "main".to_string(),
+            },
+            HotKeyAction::ResizeWindow {
+                direction: ResizeDirection::Bottom,
+                step: ResizeStep::Pixels(25),
+                history_aware: false,
+            },
+        ] {
+            let serialized = toml::to_string(&Mapping {
+                action: action.clone(),
+            })
+            .unwrap();
+            assert_eq!(
+                toml::from_str::<Mapping>(&serialized).unwrap().action,
+                action
+            );
+        }
+    }
 }
@@ -1,38 +1,177 @@
+use crate::common::action_registry::{run_action, ActionContext};
+use crate::common::config::{reload_config, Config};
+use crate::common::enums::HandlerEvent;
+use crate::common::hotkey::log_hotkey_registrations;
 use crate::common::hotkey_action::HotKeyAction;
 use crate::common::traits::Desktop;
 use crate::common::traits::HotkeyHandler;
 use crate::common::traits::Window;
+use crate::common::window_history::WindowHistory;
+use crate::Result;
+use std::path::Path;
 
-use super::action::maximize_window::implement_maximize_action_on_window;
-use super::action::minimize_window::implement_minimize_action_on_window;
-use super::action::move_window::implement_move_action_on_window;
-use super::action::move_window_to_another_screen::implement_move_action_to_another_screen;
-use super::action::resize_window::implement_resize_action_on_window;
-
-pub fn main_loop(hotkey_handler: &dyn HotkeyHandler, system: &dyn Desktop) {
+/// Handles the events of `hotkey_handler` until it sends `HandlerEvent::Quit`, then unregisters
+/// the hotkeys
+pub fn main_loop(
+    hotkey_handler: &dyn HotkeyHandler,
+    system: &dyn Desktop,
+    mut config: Config,
+    config_path: &Path,
+) {
+    let mut history = WindowHistory::new();
     loop {
-        let action = hotkey_handler.get_action_from_pressed_key();
-        let foreground_window = system.get_foreground_window();
-        implement_action_on_window(foreground_window, system, action);
+        match hotkey_handler.get_next_event() {
+            HandlerEvent::Hotkey(action) => {
+                let result = system
+                    .get_foreground_window()
+                    .and_then(|foreground_window| {
+                        implement_action_on_window(
+                            foreground_window,
+                            system,
+                            &config,
+                            action.clone(),
+                            &mut history,
+                        )
+                    });
+                if let Err(error) = result {
+                    log::error!("Couldn't run {:?}: {}", action, error);
+                }
+            }
+            HandlerEvent::ConfigChanged => {
+                if let Some(new_config) = reload_config(config_path) {
+                    if apply_hotkeys(hotkey_handler, &config, &new_config) {
+                        config = new_config;
+                    }
+                }
+            }
+            HandlerEvent::Quit => {
+                log::info!("Exiting");
+                hotkey_handler.unregister_hotkeys();
+                return;
+            }
+            HandlerEvent::Ignored => (),
+        }
+    }
+}
+
+// Replaces the registered hotkeys of `config` with the ones of `new_config`. If any of them can't
+// be registered, the hotkeys of `config` are registered again and `false` is returned, so that
+// the current configuration is kept.
+fn apply_hotkeys(hotkey_handler: &dyn HotkeyHandler, config: &Config, new_config: &Config) -> bool {
+    hotkey_handler.unregister_hotkeys();
+    let registrations = hotkey_handler.register_hotkeys(new_config.hotkeys.clone());
+    if log_hotkey_registrations(&registrations) == 0 {
+        return true;
     }
+
+    log::warn!(
+        "Not all hotkeys of the changed config file could be registered. \
+         Keeping the current configuration."
+    );
+    hotkey_handler.unregister_hotkeys();
+    let registrations = hotkey_handler.register_hotkeys(config.hotkeys.clone());
+    log_hotkey_registrations(&registrations);
+    false
+}
+
+/// Performs a single action on the foreground window, used to run actions outside the main loop
+pub fn run_single_action(
+    system: &dyn Desktop,
+    config: &Config,
+    action: HotKeyAction,
+) -> Result<()> {
+    let mut history = WindowHistory::new();
+    let foreground_window = system.get_foreground_window()?;
+    implement_action_on_window(foreground_window, system, config, action, &mut history)
 }
 
 fn implement_action_on_window(
     foreground_window: Box<dyn Window>,
     system: &dyn Desktop,
+    config: &Config,
     action: HotKeyAction,
-) {
-    if action <= HotKeyAction::MoveWindowToRightTop {
-        implement_move_action_on_window(foreground_window, action);
-    } else if action == HotKeyAction::MinimizeWindow {
-        implement_minimize_action_on_window(foreground_window);
-    } else if action == HotKeyAction::MaximizeWindow {
-        implement_maximize_action_on_window(foreground_window);
-    } else if action <= HotKeyAction::MoveWindowToRightScreenContinuous {
-        implement_move_action_to_another_screen(foreground_window, system, action);
-    } else if action <= HotKeyAction::ChangeWindowSizeTowardsRightTop {
-        implement_resize_action_on_window(foreground_window, system, action);
-    } else if action <= HotKeyAction::ChangeWindowSizeTowardsRightTopHistoryAware {
-        println!("TODO: Implement window resize (hist)");
+    history: &mut WindowHistory,
+) -> Result<()> {
+    let mut context = ActionContext {
+        system,
+        config,
+        history,
+    };
+    run_action(foreground_window, &action, &mut context)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::enums::WindowState;
+    use crate::fake::{desktop::FakeCall, desktop::FakeDesktop, hotkey_handler::FakeHotkeyHandler};
+
+    #[test]
+    fn main_loop_runs_hotkeys_until_quit() {
+        let (desktop, window) = FakeDesktop::with_monitor_and_window();
+        let handler = FakeHotkeyHandler::new(vec![
+            HandlerEvent::Hotkey(HotKeyAction::MaximizeWindow),
+            HandlerEvent::Ignored,
+            HandlerEvent::Quit,
+            HandlerEvent::Hotkey(HotKeyAction::MinimizeWindow),
+        ]);
+        let config = Config::default();
+        handler.register_hotkeys(config.hotkeys.clone());
+
+        main_loop(&handler, &desktop, config, Path::new("missing.toml"));
+        assert_eq!(desktop.window(window).state, WindowState::Maximized);
+        assert!(handler.registered_hotkeys().is_empty());
+        assert_eq!(
+            handler.get_next_event(),
+            HandlerEvent::Hotkey(HotKeyAction::MinimizeWindow)
+        );
+    }
+
+    #[test]
+    fn main_loop_keeps_running_after_a_failed_action() {
+        // No windows, so there's no foreground window to act on
+        let desktop = FakeDesktop::new();
+        let handler = FakeHotkeyHandler::new(vec![
+            HandlerEvent::Hotkey(HotKeyAction::MaximizeWindow),
+            HandlerEvent::Hotkey(HotKeyAction::MinimizeWindow),
+        ]);
+        handler.get_quit_notifier()();
+
+        main_loop(
+            &handler,
+            &desktop,
+            Config::default(),
+            Path::new("missing.toml"),
+        );
+        // Both hotkeys were handled
+        assert_eq!(
+            desktop.take_calls(),
+            vec![FakeCall::GetForegroundWindow, FakeCall::GetForegroundWindow]
+        );
+    }
+
+    #[test]
+    fn reload_keeps_the_current_hotkeys_if_the_new_ones_fail() {
+        let handler = FakeHotkeyHandler::new(vec![]);
+        let config = Config::default();
+        handler.register_hotkeys(config.hotkeys.clone());
+        let current_hotkeys = handler.registered_hotkeys();
+        handler.take_hotkey("Ctrl+Alt+T".parse().unwrap());
+
+        let new_config = |hotkey: &str| -> Config {
+            toml::from_str(&format!(
+                "[[hotkeys]]\naction = \"MaximizeWindow\"\nhotkey = \"Ctrl+Alt+M\"\n\
+                 [[hotkeys]]\naction = \"MinimizeWindow\"\nhotkey = \"{}\"\n",
+                hotkey
+            ))
+            .unwrap()
+        };
+
+        assert!(!apply_hotkeys(&handler, &config, &new_config("Ctrl+Alt+T")));
+        assert_eq!(handler.registered_hotkeys(), current_hotkeys);
+
+        let new_config = new_config("Ctrl+Alt+N");
+        assert!(apply_hotkeys(&handler, &config, &new_config));
+        assert_eq!(handler.registered_hotkeys(), new_config.hotkeys);
     }
 }
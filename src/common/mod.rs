@@ -1,8 +1,16 @@
 pub mod action;
+pub mod action_registry;
 pub mod calculation;
+pub mod cli;
 pub mod config;
+pub mod config_path;
+pub mod config_validation;
+pub mod config_watcher;
 pub mod enums;
+pub mod hotkey;
 pub mod hotkey_action;
 pub mod logic;
+pub mod monitor_topology;
 pub mod structs;
 pub mod traits;
+pub mod window_history;
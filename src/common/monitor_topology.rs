@@ -0,0 +1,268 @@
+use crate::common::structs::Rect;
+
+// Monitors are considered to share an edge if the edges are at most this far apart
+const EDGE_TOLERANCE: i32 = 2;
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Direction {
+    Left,
+    Right,
+    Up,
+    Down,
+}
+
+impl Direction {
+    pub fn opposite(&self) -> Direction {
+        match self {
+            Direction::Left => Direction::Right,
+            Direction::Right => Direction::Left,
+            Direction::Up => Direction::Down,
+            Direction::Down => Direction::Up,
+        }
+    }
+
+    fn index(&self) -> usize {
+        match self {
+            Direction::Left => 0,
+            Direction::Right => 1,
+            Direction::Up => 2,
+            Direction::Down => 3,
+        }
+    }
+}
+
+/// Graph of monitors and their neighbours in each direction, built from the monitors' rects
+#[derive(Debug)]
+pub struct MonitorTopology {
+    neighbours: Vec<[Option<usize>; 4]>,
+}
+
+impl MonitorTopology {
+    /// Builds the graph from the monitors' rects, the indexes of `monitor_rects` are used to
+    /// refer to the monitors.
+    ///
+    /// The neighbour of a monitor in a direction is the monitor sharing that edge with the largest
+    /// overlap. If no monitor shares the edge, the closest monitor in that direction overlapping
+    /// on the other axis is used, and after that the monitor with the closest middle point in
+    /// that direction.
+    pub fn new(monitor_rects: &[Rect]) -> Self {
+        let neighbours = (0..monitor_rects.len())
+            .map(|index| {
+                let mut neighbours = [None; 4];
+                for direction in [
+                    Direction::Left,
+                    Direction::Right,
+                    Direction::Up,
+                    Direction::Down,
+                ] {
+                    neighbours[direction.index()] = find_neighbour(monitor_rects, index, direction);
+                }
+                neighbours
+            })
+            .collect();
+        MonitorTopology { neighbours }
+    }
+
+    pub fn get_neighbour(&self, index: usize, direction: Direction) -> Option<usize> {
+        self.neighbours.get(index)?[direction.index()]
+    }
+
+    /// Returns the neighbour in `direction`. If there's none, wraps around to the monitor furthest
+    /// away in the opposite direction. Returns `None` if the monitor is alone on that axis.
+    pub fn get_neighbour_wrapping(&self, index: usize, direction: Direction) -> Option<usize> {
+        if let Some(neighbour) = self.get_neighbour(index, direction) {
+            return Some(neighbour);
+        }
+
+        let mut visited = vec![index];
+        let mut current = index;
+        while let Some(next) = self.get_neighbour(current, direction.opposite()) {
+            if visited.contains(&next) {
+                break;
+            }
+            visited.push(next);
+            current = next;
+        }
+
+        if current == index {
+            None
+        } else {
+            Some(current)
+        }
+    }
+}
+
+// Length of the overlap of two ranges, negative if they don't overlap
+fn overlap(a_start: i32, a_end: i32, b_start: i32, b_end: i32) -> i32 {
+    a_end.min(b_end) - a_start.max(b_start)
+}
+
+/// Index of the monitor `window` overlaps the most, or the one closest to it if it's off-screen,
+/// like `MONITOR_DEFAULTTONEAREST` on Windows
+pub fn find_nearest_monitor(monitor_rects: &[Rect], window: &Rect) -> Option<usize> {
+    let horizontal = |rect: &Rect| overlap(rect.left, rect.right, window.left, window.right);
+    let vertical = |rect: &Rect| overlap(rect.top, rect.bottom, window.top, window.bottom);
+    let overlap_area =
+        |rect: &Rect| i64::from(horizontal(rect).max(0)) * i64::from(vertical(rect).max(0));
+    // A negative overlap is the gap between the ranges
+    let distance = |rect: &Rect| {
+        let dx = i64::from((-horizontal(rect)).max(0));
+        let dy = i64::from((-vertical(rect)).max(0));
+        dx * dx + dy * dy
+    };
+
+    let most_overlap =
+        (0..monitor_rects.len()).max_by_key(|&index| overlap_area(&monitor_rects[index]))?;
+    if overlap_area(&monitor_rects[most_overlap]) > 0 {
+        return Some(most_overlap);
+    }
+    (0..monitor_rects.len()).min_by_key(|&index| distance(&monitor_rects[index]))
+}
+
+fn find_neighbour(monitor_rects: &[Rect], index: usize, direction: Direction) -> Option<usize> {
+    let rect = &monitor_rects[index];
+    let middle = rect.middle_point();
+    let others = || {
+        monitor_rects
+            .iter()
+            .enumerate()
+            .filter(move |(other_index, _)| *other_index != index)
+    };
+
+    // (distance between the facing edges, overlap on the other axis)
+    let edge_distance_and_overlap = |other: &Rect| match direction {
+        Direction::Left => (
+            rect.left - other.right,
+            overlap(rect.top, rect.bottom, other.top, other.bottom),
+        ),
+        Direction::Right => (
+            other.left - rect.right,
+            overlap(rect.top, rect.bottom, other.top, other.bottom),
+        ),
+        Direction::Up => (
+            rect.top - other.bottom,
+            overlap(rect.left, rect.right, other.left, other.right),
+        ),
+        Direction::Down => (
+            other.top - rect.bottom,
+            overlap(rect.left, rect.right, other.left, other.right),
+        ),
+    };
+
+    // Monitors sharing the edge
+    let shared_edge = others()
+        .map(|(other_index, other)| (other_index, edge_distance_and_overlap(other)))
+        .filter(|(_, (distance, overlap))| distance.abs() <= EDGE_TOLERANCE && *overlap > 0)
+        .max_by_key(|(_, (_, overlap))| *overlap)
+        .map(|(other_index, _)| other_index);
+    if shared_edge.is_some() {
+        return shared_edge;
+    }
+
+    // Monitors further away in the direction
+    let in_line = others()
+        .map(|(other_index, other)| (other_index, edge_distance_and_overlap(other)))
+        .filter(|(_, (distance, overlap))| *distance >= 0 && *overlap > 0)
+        .min_by_key(|(_, (distance, _))| *distance)
+        .map(|(other_index, _)| other_index);
+    if in_line.is_some() {
+        return in_line;
+    }
+
+    // Monitors diagonally in the direction
+    others()
+        .map(|(other_index, other)| {
+            let other_middle = other.middle_point();
+            let dx = other_middle.x - middle.x;
+            let dy = other_middle.y - middle.y;
+            let is_in_direction = match direction {
+                Direction::Left => dx < 0 && dx.abs() >= dy.abs(),
+                Direction::Right => dx > 0 && dx.abs() >= dy.abs(),
+                Direction::Up => dy < 0 && dy.abs() > dx.abs(),
+                Direction::Down => dy > 0 && dy.abs() > dx.abs(),
+            };
+            (
+                other_index,
+                is_in_direction,
+                dx as i64 * dx as i64 + dy as i64 * dy as i64,
+            )
+        })
+        .filter(|(_, is_in_direction, _)| *is_in_direction)
+        .min_by_key(|(_, _, distance)| *distance)
+        .map(|(other_index, _, _)| other_index)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rect(left: i32, top: i32, width: i32, height: i32) -> Rect {
+        Rect {
+            left,
+            top,
+            right: left + width,
+            bottom: top + height,
+        }
+    }
+
+    #[test]
+    fn side_by_side_monitors() {
+        let topology = MonitorTopology::new(&[
+            rect(0, 0, 1920, 1080),
+            rect(-1920, 0, 1920, 1080),
+            rect(1920, 0, 2560, 1440),
+        ]);
+        assert_eq!(topology.get_neighbour(0, Direction::Left), Some(1));
+        assert_eq!(topology.get_neighbour(0, Direction::Right), Some(2));
+        assert_eq!(topology.get_neighbour(0, Direction::Up), None);
+        assert_eq!(topology.get_neighbour(1, Direction::Left), None);
+        assert_eq!(topology.get_neighbour_wrapping(1, Direction::Left), Some(2));
+        assert_eq!(
+            topology.get_neighbour_wrapping(2, Direction::Right),
+            Some(1)
+        );
+    }
+
+    #[test]
+    fn stacked_monitors() {
+        let topology = MonitorTopology::new(&[rect(0, 0, 1920, 1080), rect(0, -1440, 2560, 1440)]);
+        assert_eq!(topology.get_neighbour(0, Direction::Up), Some(1));
+        assert_eq!(topology.get_neighbour(1, Direction::Down), Some(0));
+        assert_eq!(topology.get_neighbour(0, Direction::Left), None);
+        assert_eq!(topology.get_neighbour_wrapping(0, Direction::Left), None);
+    }
+
+    #[test]
+    fn l_shaped_monitors() {
+        // Two monitors side by side and a third one above the left one
+        let topology = MonitorTopology::new(&[
+            rect(0, 0, 1920, 1080),
+            rect(1920, 0, 1920, 1080),
+            rect(0, -1080, 1920, 1080),
+        ]);
+        assert_eq!(topology.get_neighbour(0, Direction::Up), Some(2));
+        assert_eq!(topology.get_neighbour(0, Direction::Right), Some(1));
+        assert_eq!(topology.get_neighbour(2, Direction::Down), Some(0));
+        assert_eq!(topology.get_neighbour(1, Direction::Up), None);
+        // Diagonal neighbour
+        assert_eq!(topology.get_neighbour(2, Direction::Right), Some(1));
+    }
+
+    #[test]
+    fn finds_monitor_with_most_overlap() {
+        let monitors = [rect(0, 0, 1920, 1080), rect(1920, 0, 1920, 1080)];
+        assert_eq!(
+            find_nearest_monitor(&monitors, &rect(100, 100, 500, 500)),
+            Some(0)
+        );
+        assert_eq!(
+            find_nearest_monitor(&monitors, &rect(1800, 100, 800, 500)),
+            Some(1)
+        );
+        assert_eq!(
+            find_nearest_monitor(&monitors, &rect(4000, 100, 500, 500)),
+            Some(1)
+        );
+        assert_eq!(find_nearest_monitor(&[], &rect(0, 0, 10, 10)), None);
+    }
+}
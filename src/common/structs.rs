@@ -1,11 +1,281 @@
 use crate::common::enums::{HotKeyButton, HotKeyModifier};
+use crate::common::hotkey::Hotkey;
 use crate::common::hotkey_action::HotKeyAction;
 
-#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(try_from = "RawHotkeyMapping")]
 pub struct HotkeyMapping {
     pub action: HotKeyAction,
-    pub key: HotKeyButton,
-    pub modifier: HotKeyModifier,
+    pub hotkey: Hotkey,
+}
+
+// Hotkey mapping as written in the configuration. The hotkey is either a string like
+// `hotkey = "Ctrl+Alt+Left"` or given with the older `key` and `modifier` fields.
+#[derive(serde::Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RawHotkeyMapping {
+    action: HotKeyAction,
+    #[serde(default)]
+    hotkey: Option<Hotkey>,
+    #[serde(default)]
+    key: Option<HotKeyButton>,
+    #[serde(default)]
+    modifier: Option<HotKeyModifier>,
+}
+
+impl TryFrom<RawHotkeyMapping> for HotkeyMapping {
+    type Error = String;
+
+    fn try_from(raw: RawHotkeyMapping) -> Result<Self, Self::Error> {
+        let hotkey = match (raw.hotkey, raw.key, raw.modifier) {
+            (Some(hotkey), None, None) => hotkey,
+            (None, Some(key), modifier) => Hotkey {
+                key,
+                modifiers: modifier.map(Into::into).unwrap_or_default(),
+            },
+            (Some(_), _, _) => {
+                return Err("'hotkey' can't be combined with 'key' or 'modifier'".to_string())
+            }
+            (None, None, _) => return Err("missing field `hotkey`".to_string()),
+        };
+        Ok(HotkeyMapping {
+            action: raw.action,
+            hotkey,
+        })
+    }
+}
+
+/// Sizes a window cycles through when the same move action is pressed repeatedly
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct SizeCycle {
+    pub action: HotKeyAction,
+    /// Fractions of the monitor's width, or height for `MoveWindowToTop`/`MoveWindowToBottom`
+    pub fractions: Vec<f32>,
+}
+
+/// Space left between snapped windows and around them at the monitor's edges, in pixels
+#[derive(Debug, Copy, Clone, PartialEq, Default)]
+pub struct Gaps {
+    pub inner_gap: i32,
+    pub outer_padding: i32,
+}
+
+/// Settings of a single monitor, overriding the global ones
+#[derive(Debug, Clone, Default, serde::Serialize, serde::Deserialize)]
+pub struct MonitorConfig {
+    /// Index of the monitor in the configured monitor order, starting from 0
+    pub index: usize,
+    /// Name used to refer to the monitor in actions
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub inner_gap: Option<i32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub outer_padding: Option<i32>,
+}
+
+/// Monitor targeted by `MoveWindowToMonitor`
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MonitorTarget {
+    /// Index of the monitor in the configured monitor order, starting from 0
+    Index(usize),
+    /// Name given to the monitor in `[[monitors]]`
+    Name(String),
+}
+
+/// Zone defined in the configuration, windows are moved into it with `MoveWindowToZone`
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct Zone {
+    pub name: String,
+    /// Index of the monitor in the configured monitor order. Applies to all monitors if not set.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub monitor: Option<usize>,
+    pub x: ZoneValue,
+    pub y: ZoneValue,
+    pub width: ZoneValue,
+    pub height: ZoneValue,
+}
+
+/// Coordinate or size of a `Zone`, relative to the monitor's work area
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum ZoneValue {
+    /// Pixels, written as an integer: `1280`
+    Pixels(i32),
+    /// Fraction of the monitor, written as a percentage: `"60%"`
+    Fraction(f32),
+    /// Centered on the monitor, written as `"center"`. For sizes this is the whole monitor.
+    Center,
+}
+
+impl ZoneValue {
+    pub fn parse(value: &str) -> Result<ZoneValue, String> {
+        let value = value.trim();
+        if value.eq_ignore_ascii_case("center") {
+            return Ok(ZoneValue::Center);
+        }
+        if let Some(percentage) = value.strip_suffix('%') {
+            return percentage
+                .trim()
+                .parse::<f32>()
+                .map(|percentage| ZoneValue::Fraction(percentage / 100.0))
+                .map_err(|_| format!("invalid percentage '{}'", value));
+        }
+        value
+            .strip_suffix("px")
+            .unwrap_or(value)
+            .trim()
+            .parse::<i32>()
+            .map(ZoneValue::Pixels)
+            .map_err(|_| {
+                format!(
+                    "invalid zone value '{}', expected pixels, a percentage or \"center\"",
+                    value
+                )
+            })
+    }
+}
+
+impl std::fmt::Display for ZoneValue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ZoneValue::Pixels(pixels) => write!(f, "{}px", pixels),
+            ZoneValue::Fraction(fraction) => write!(f, "{}%", fraction * 100.0),
+            ZoneValue::Center => write!(f, "center"),
+        }
+    }
+}
+
+impl serde::Serialize for ZoneValue {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            ZoneValue::Pixels(pixels) => serializer.serialize_i32(*pixels),
+            _ => serializer.serialize_str(&self.to_string()),
+        }
+    }
+}
+
+impl<'de> serde::Deserialize<'de> for ZoneValue {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(serde::Deserialize)]
+        #[serde(untagged)]
+        enum RawZoneValue {
+            Pixels(i32),
+            Text(String),
+        }
+
+        match RawZoneValue::deserialize(deserializer)? {
+            RawZoneValue::Pixels(pixels) => Ok(ZoneValue::Pixels(pixels)),
+            RawZoneValue::Text(text) => ZoneValue::parse(&text).map_err(serde::de::Error::custom),
+        }
+    }
+}
+
+/// Amount a window grows or shrinks by on each resize
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum ResizeStep {
+    /// Pixels, written as an integer: `50`
+    Pixels(i32),
+    /// Fraction of the monitor, written as a percentage: `"5%"`
+    Fraction(f32),
+}
+
+impl Default for ResizeStep {
+    fn default() -> Self {
+        ResizeStep::Fraction(0.1)
+    }
+}
+
+impl ResizeStep {
+    pub fn parse(value: &str) -> Result<ResizeStep, String> {
+        match ZoneValue::parse(value) {
+            Ok(ZoneValue::Pixels(pixels)) => Ok(ResizeStep::Pixels(pixels)),
+            Ok(ZoneValue::Fraction(fraction)) => Ok(ResizeStep::Fraction(fraction)),
+            _ => Err(format!(
+                "invalid resize step '{}', expected pixels or a percentage",
+                value.trim()
+            )),
+        }
+    }
+
+    /// Returns the step in pixels for a monitor of the given width or height
+    pub fn to_pixels(&self, monitor_length: i32) -> i32 {
+        match self {
+            ResizeStep::Pixels(pixels) => *pixels,
+            ResizeStep::Fraction(fraction) => (monitor_length as f32 * fraction) as i32,
+        }
+    }
+}
+
+impl std::fmt::Display for ResizeStep {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ResizeStep::Pixels(pixels) => write!(f, "{}px", pixels),
+            ResizeStep::Fraction(fraction) => write!(f, "{}%", fraction * 100.0),
+        }
+    }
+}
+
+impl serde::Serialize for ResizeStep {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            ResizeStep::Pixels(pixels) => serializer.serialize_i32(*pixels),
+            ResizeStep::Fraction(_) => serializer.serialize_str(&self.to_string()),
+        }
+    }
+}
+
+impl<'de> serde::Deserialize<'de> for ResizeStep {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(serde::Deserialize)]
+        #[serde(untagged)]
+        enum RawResizeStep {
+            Pixels(i32),
+            Text(String),
+        }
+
+        match RawResizeStep::deserialize(deserializer)? {
+            RawResizeStep::Pixels(pixels) => Ok(ResizeStep::Pixels(pixels)),
+            RawResizeStep::Text(text) => ResizeStep::parse(&text).map_err(serde::de::Error::custom),
+        }
+    }
+}
+
+/// Grid of equally sized cells a monitor is divided into
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct GridLayout {
+    /// Index of the monitor in the configured monitor order, starting from 0.
+    /// Applies to all monitors if not set.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub monitor: Option<usize>,
+    pub columns: u32,
+    pub rows: u32,
+}
+
+impl Default for GridLayout {
+    fn default() -> Self {
+        GridLayout {
+            monitor: None,
+            columns: 2,
+            rows: 2,
+        }
+    }
+}
+
+/// Cell of a `GridLayout`, optionally spanning over several cells to the right and down
+#[derive(Debug, Copy, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct GridCell {
+    pub column: u32,
+    pub row: u32,
+    #[serde(default = "default_span")]
+    pub column_span: u32,
+    #[serde(default = "default_span")]
+    pub row_span: u32,
+}
+
+fn default_span() -> u32 {
+    1
 }
 
 #[derive(Debug, PartialEq, Copy, Clone)]
@@ -35,7 +305,7 @@ pub struct WindowPosition {
     pub height: i32,
 }
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, PartialEq, Clone, Default)]
 pub struct Rect {
     pub left: i32,
     pub right: i32,
@@ -63,17 +333,6 @@ impl Rect {
     }
 }
 
-impl Default for Rect {
-    fn default() -> Self {
-        Rect {
-            left: 0,
-            right: 0,
-            top: 0,
-            bottom: 0,
-        }
-    }
-}
-
 // TODO: temporary
 impl From<&Rect> for WindowPosition {
     fn from(value: &Rect) -> Self {
@@ -105,7 +364,7 @@ pub struct Point {
 }
 
 #[allow(dead_code)]
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct WindowBorderSize {
     pub left: i32,
     pub right: i32,
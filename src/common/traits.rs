@@ -1,36 +1,135 @@
+use crate::Result;
+
 use super::{
-    enums::WindowState,
-    hotkey_action::HotKeyAction,
+    enums::{HandlerEvent, MonitorOrder, WindowState},
+    hotkey::HotkeyRegistration,
     structs::{DpiInfo, HotkeyMapping, Rect, WindowBorderSize},
 };
 
 pub trait Desktop {
-    fn get_foreground_window(&self) -> Box<dyn Window>;
-    fn get_all_monitors(&self) -> Vec<Box<dyn Monitor>>;
+    fn get_foreground_window(&self) -> Result<Box<dyn Window>>;
+    fn get_all_monitors(&self) -> Result<Vec<Box<dyn Monitor>>>;
+    fn get_all_windows(&self) -> Result<Vec<Box<dyn Window>>>;
+
+    /// All monitors sorted in `order`
+    fn get_ordered_monitors(&self, order: MonitorOrder) -> Result<Vec<Box<dyn Monitor>>> {
+        let mut all_monitors = self
+            .get_all_monitors()?
+            .into_iter()
+            .map(|m| Ok((m.get_size()?, m)))
+            .collect::<Result<Vec<_>>>()?;
+        all_monitors.sort_by_key(|(size, _)| match order {
+            MonitorOrder::LeftToRight => (size.left, size.top),
+            MonitorOrder::TopToBottom => (size.top, size.left),
+        });
+        Ok(all_monitors.into_iter().map(|(_, m)| m).collect())
+    }
+
+    /// Index of `monitor` when monitors are sorted in `order`
+    fn get_monitor_index(
+        &self,
+        monitor: &dyn Monitor,
+        order: MonitorOrder,
+    ) -> Result<Option<usize>> {
+        Ok(self
+            .get_ordered_monitors(order)?
+            .iter()
+            .position(|m| m.equals(monitor)))
+    }
 }
 
 pub trait Window {
-    fn move_window(&self, windows_rect: &Rect);
-    fn get_position(&self) -> Rect;
-    fn get_state(&self) -> WindowState;
-    fn restore(&self);
-    fn minimize(&self);
-    fn maximize(&self);
-    fn disable_snapping(&self);
-    fn get_margin(&self) -> WindowBorderSize;
-    fn get_current_monitor(&self) -> Box<dyn Monitor>;
+    fn move_window(&self, windows_rect: &Rect) -> Result<()>;
+    fn get_position(&self) -> Result<Rect>;
+    fn get_state(&self) -> Result<WindowState>;
+    fn restore(&self) -> Result<()>;
+    fn minimize(&self) -> Result<()>;
+    fn maximize(&self) -> Result<()>;
+    fn disable_snapping(&self) -> Result<()>;
+    fn get_margin(&self) -> Result<WindowBorderSize>;
+    fn get_current_monitor(&self) -> Result<Box<dyn Monitor>>;
+    fn get_platform_specific_handle(&self) -> isize;
 }
 
 pub trait Monitor {
-    fn get_size(&self) -> Rect;
-    fn get_dpi_info(&self) -> DpiInfo;
+    fn get_size(&self) -> Result<Rect>;
+    fn get_dpi_info(&self) -> Result<DpiInfo>;
     fn get_platform_specific_handle(&self) -> isize;
-    fn equals(&self, other: &Box<dyn Monitor>) -> bool {
+    fn equals(&self, other: &dyn Monitor) -> bool {
         self.get_platform_specific_handle() == other.get_platform_specific_handle()
     }
 }
 
 pub trait HotkeyHandler {
-    fn register_hotkeys(&self, keys: Vec<HotkeyMapping>);
-    fn get_action_from_pressed_key(&self) -> HotKeyAction;
+    /// Registers the hotkeys and returns the outcome of each mapping, in the same order
+    fn register_hotkeys(&self, keys: Vec<HotkeyMapping>) -> Vec<HotkeyRegistration>;
+    /// Unregisters all hotkeys registered by `register_hotkeys`
+    fn unregister_hotkeys(&self);
+    /// Waits for the next hotkey press or other event
+    fn get_next_event(&self) -> HandlerEvent;
+    /// Returns a function that can be called from any thread to send `HandlerEvent::ConfigChanged`
+    fn get_config_change_notifier(&self) -> Box<dyn Fn() + Send>;
+    /// Returns a function that can be called from any thread to send `HandlerEvent::Quit`
+    fn get_quit_notifier(&self) -> Box<dyn Fn() + Send>;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fake::{desktop::FakeDesktop, monitor::FakeMonitor};
+
+    // Three monitors in an L shape, added in neither order
+    fn desktop() -> FakeDesktop {
+        FakeDesktop::with_monitors([
+            FakeMonitor::full_hd_at(3, 0, 1080),
+            FakeMonitor::full_hd_at(2, 1920, 0),
+            FakeMonitor::full_hd_at(1, 0, 0),
+        ])
+    }
+
+    fn ordered_handles(desktop: &FakeDesktop, order: MonitorOrder) -> Vec<isize> {
+        desktop
+            .get_ordered_monitors(order)
+            .unwrap()
+            .iter()
+            .map(|monitor| monitor.get_platform_specific_handle())
+            .collect()
+    }
+
+    #[test]
+    fn orders_monitors_left_to_right() {
+        assert_eq!(
+            ordered_handles(&desktop(), MonitorOrder::LeftToRight),
+            vec![1, 3, 2]
+        );
+    }
+
+    #[test]
+    fn orders_monitors_top_to_bottom() {
+        assert_eq!(
+            ordered_handles(&desktop(), MonitorOrder::TopToBottom),
+            vec![1, 2, 3]
+        );
+    }
+
+    #[test]
+    fn monitor_index_follows_the_order() {
+        let desktop = desktop();
+        let lower = FakeMonitor::full_hd_at(3, 0, 1080);
+        assert_eq!(
+            desktop.get_monitor_index(&lower, MonitorOrder::LeftToRight),
+            Ok(Some(1))
+        );
+        assert_eq!(
+            desktop.get_monitor_index(&lower, MonitorOrder::TopToBottom),
+            Ok(Some(2))
+        );
+        assert_eq!(
+            desktop.get_monitor_index(
+                &FakeMonitor::full_hd_at(4, 3840, 0),
+                MonitorOrder::LeftToRight
+            ),
+            Ok(None)
+        );
+    }
 }
@@ -0,0 +1,157 @@
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use crate::common::{hotkey_action::HotKeyAction, structs::Rect, traits::Window};
+use crate::Result;
+
+/// Keeps track of the zones win-move has placed windows into
+pub struct WindowHistory {
+    zones: HashMap<isize, Placement>,
+    last_move: Option<LastMove>,
+}
+
+// Zone a window was placed into and the position it was left at. A window at another position
+// has been moved since, eg. by hand, and isn't in the zone anymore.
+struct Placement {
+    action: HotKeyAction,
+    rect: Rect,
+}
+
+// The latest move action, used to detect repeated presses of the same hotkey
+struct LastMove {
+    window_handle: isize,
+    action: HotKeyAction,
+    time: Instant,
+    fraction_index: usize,
+}
+
+impl WindowHistory {
+    pub fn new() -> Self {
+        Self {
+            zones: HashMap::new(),
+            last_move: None,
+        }
+    }
+
+    /// Returns the size fraction the window should take when `action` is applied on it at `now`.
+    ///
+    /// Pressing the same action again on the same window within `timeout` advances to the next
+    /// fraction in `fractions`, wrapping around at the end. Any other press starts from the first
+    /// fraction. Returns `None` if `fractions` is empty.
+    pub fn next_size_fraction(
+        &mut self,
+        window_handle: isize,
+        action: &HotKeyAction,
+        fractions: &[f32],
+        timeout: Duration,
+        now: Instant,
+    ) -> Option<f32> {
+        if fractions.is_empty() {
+            self.last_move = None;
+            return None;
+        }
+
+        let fraction_index = match &self.last_move {
+            Some(last_move)
+                if last_move.window_handle == window_handle
+                    && last_move.action == *action
+                    && now.duration_since(last_move.time) <= timeout =>
+            {
+                (last_move.fraction_index + 1) % fractions.len()
+            }
+            _ => 0,
+        };
+
+        self.last_move = Some(LastMove {
+            window_handle,
+            action: action.clone(),
+            time: now,
+            fraction_index,
+        });
+        Some(fractions[fraction_index])
+    }
+
+    /// Records that the window was placed into the zone of `action`, at its current position
+    pub fn record(&mut self, window: &dyn Window, action: HotKeyAction) -> Result<()> {
+        let rect = window.get_position()?;
+        self.zones.insert(
+            window.get_platform_specific_handle(),
+            Placement { action, rect },
+        );
+        Ok(())
+    }
+
+    /// Updates the position of a window win-move resized within its zone
+    pub fn update_position(&mut self, window: &dyn Window) -> Result<()> {
+        if let Some(placement) = self.zones.get_mut(&window.get_platform_specific_handle()) {
+            placement.rect = window.get_position()?;
+        }
+        Ok(())
+    }
+
+    /// Forgets the windows that aren't in `windows` anymore, eg. because they were closed, and
+    /// the windows moved away from the position win-move left them at
+    pub fn prune(&mut self, windows: &[Box<dyn Window>]) {
+        self.zones.retain(|handle, placement| {
+            windows.iter().any(|window| {
+                window.get_platform_specific_handle() == *handle
+                    && window.get_position().as_ref() == Ok(&placement.rect)
+            })
+        });
+    }
+
+    pub fn get_zone(&self, window: &dyn Window) -> Option<HotKeyAction> {
+        self.zones
+            .get(&window.get_platform_specific_handle())
+            .map(|placement| placement.action.clone())
+    }
+
+    pub fn is_snapped(&self, window: &dyn Window) -> bool {
+        self.get_zone(window).is_some()
+    }
+}
+
+impl Default for WindowHistory {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn repeated_presses_cycle_through_fractions() {
+        let mut history = WindowHistory::new();
+        let fractions = [0.5, 0.33, 0.67];
+        let timeout = Duration::from_millis(1000);
+        let start = Instant::now();
+        let action = HotKeyAction::MoveWindowToLeftMiddle;
+
+        let mut next = |handle, action: &HotKeyAction, millis| {
+            history.next_size_fraction(
+                handle,
+                action,
+                &fractions,
+                timeout,
+                start + Duration::from_millis(millis),
+            )
+        };
+
+        assert_eq!(next(1, &action, 0), Some(0.5));
+        assert_eq!(next(1, &action, 100), Some(0.33));
+        assert_eq!(next(1, &action, 200), Some(0.67));
+        assert_eq!(next(1, &action, 300), Some(0.5));
+        // Another window, another action or a press after the timeout start from the beginning
+        assert_eq!(next(2, &action, 400), Some(0.5));
+        assert_eq!(
+            next(2, &HotKeyAction::MoveWindowToRightMiddle, 500),
+            Some(0.5)
+        );
+        assert_eq!(
+            next(2, &HotKeyAction::MoveWindowToRightMiddle, 2000),
+            Some(0.5)
+        );
+    }
+}
@@ -0,0 +1,55 @@
+use std::fmt;
+
+/// Errors from the desktop, its windows and monitors
+#[derive(Debug, Clone, PartialEq)]
+pub enum Error {
+    /// Couldn't connect to the window system
+    Connection(String),
+    /// A call to the operating system or window system failed
+    Os {
+        operation: &'static str,
+        message: String,
+    },
+    /// No window has the focus, eg. the desktop itself is focused
+    NoForegroundWindow,
+    /// The window doesn't exist anymore
+    WindowNotFound(isize),
+    /// No monitors were found
+    NoMonitors,
+    /// An action was passed to the handler of another action
+    UnhandledAction {
+        handler: &'static str,
+        action: String,
+    },
+}
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+impl Error {
+    /// Failure of the OS call `operation`
+    pub fn os(operation: &'static str, error: impl fmt::Display) -> Self {
+        Error::Os {
+            operation,
+            message: error.to_string(),
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Connection(message) => {
+                write!(f, "couldn't connect to the window system: {}", message)
+            }
+            Error::Os { operation, message } => write!(f, "{} failed: {}", operation, message),
+            Error::NoForegroundWindow => write!(f, "no window has the focus"),
+            Error::WindowNotFound(handle) => write!(f, "window {} doesn't exist", handle),
+            Error::NoMonitors => write!(f, "no monitors found"),
+            Error::UnhandledAction { handler, action } => {
+                write!(f, "{} can't run {}", handler, action)
+            }
+        }
+    }
+}
+
+impl std::error::Error for Error {}
@@ -0,0 +1,287 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use crate::common::enums::WindowState;
+use crate::common::structs::{Rect, WindowBorderSize};
+use crate::common::traits::{Desktop, Monitor, Window};
+use crate::{Error, Result};
+
+use super::monitor::FakeMonitor;
+use super::window::{FakeWindow, FakeWindowState};
+
+/// Call made to a `FakeDesktop` or one of its windows
+#[derive(Debug, Clone, PartialEq)]
+pub enum FakeCall {
+    GetForegroundWindow,
+    GetAllMonitors,
+    GetAllWindows,
+    MoveWindow { window: isize, rect: Rect },
+    GetPosition { window: isize },
+    GetState { window: isize },
+    Restore { window: isize },
+    Minimize { window: isize },
+    Maximize { window: isize },
+    DisableSnapping { window: isize },
+    GetMargin { window: isize },
+    GetCurrentMonitor { window: isize },
+}
+
+#[derive(Debug, Default)]
+pub(crate) struct FakeDesktopState {
+    pub monitors: Vec<FakeMonitor>,
+    pub windows: Vec<FakeWindowState>,
+    pub foreground_window: Option<isize>,
+    pub calls: Vec<FakeCall>,
+    // Handles aren't reused after a window is closed, like on a real desktop
+    pub next_handle: isize,
+}
+
+impl FakeDesktopState {
+    pub fn window(&self, handle: isize) -> Result<&FakeWindowState> {
+        self.windows
+            .iter()
+            .find(|window| window.handle == handle)
+            .ok_or(Error::WindowNotFound(handle))
+    }
+
+    pub fn window_mut(&mut self, handle: isize) -> Result<&mut FakeWindowState> {
+        self.windows
+            .iter_mut()
+            .find(|window| window.handle == handle)
+            .ok_or(Error::WindowNotFound(handle))
+    }
+}
+
+/// In-memory desktop with monitors and windows. Every call to it and its windows is recorded.
+#[derive(Default)]
+pub struct FakeDesktop {
+    state: Rc<RefCell<FakeDesktopState>>,
+}
+
+impl FakeDesktop {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Desktop with a single 1920x1040 monitor and a normal window at 100,100–900,700 on it. Returns
+    /// the desktop and the handle of the window, which is the foreground window.
+    pub fn with_monitor_and_window() -> (Self, isize) {
+        let desktop = Self::with_monitors([FakeMonitor::new(
+            1,
+            Rect {
+                left: 0,
+                right: 1920,
+                top: 0,
+                bottom: 1040,
+            },
+        )]);
+        let window = desktop.add_window(Rect {
+            left: 100,
+            right: 900,
+            top: 100,
+            bottom: 700,
+        });
+        (desktop, window)
+    }
+
+    /// Desktop with the given monitors and no windows
+    pub fn with_monitors(monitors: impl IntoIterator<Item = FakeMonitor>) -> Self {
+        let desktop = Self::new();
+        for monitor in monitors {
+            desktop.add_monitor(monitor);
+        }
+        desktop
+    }
+
+    pub fn add_monitor(&self, monitor: FakeMonitor) {
+        self.state.borrow_mut().monitors.push(monitor);
+    }
+
+    /// Adds a normal window at `rect` and returns its handle. The first window added is the
+    /// foreground window.
+    pub fn add_window(&self, rect: Rect) -> isize {
+        let mut state = self.state.borrow_mut();
+        state.next_handle += 1;
+        let handle = state.next_handle;
+        state.windows.push(FakeWindowState::new(handle, rect));
+        state.foreground_window.get_or_insert(handle);
+        handle
+    }
+
+    /// Closes the window, after which calls to it fail with `Error::WindowNotFound`. Closing the
+    /// foreground window leaves the desktop without one.
+    pub fn close_window(&self, handle: isize) {
+        let mut state = self.state.borrow_mut();
+        state.windows.retain(|window| window.handle != handle);
+        if state.foreground_window == Some(handle) {
+            state.foreground_window = None;
+        }
+    }
+
+    pub fn set_foreground_window(&self, handle: isize) {
+        self.state.borrow_mut().foreground_window = Some(handle);
+    }
+
+    pub fn set_window_margin(&self, handle: isize, margin: WindowBorderSize) {
+        self.state.borrow_mut().window_mut(handle).unwrap().margin = margin;
+    }
+
+    pub fn set_window_state(&self, handle: isize, state: WindowState) {
+        self.state.borrow_mut().window_mut(handle).unwrap().state = state;
+    }
+
+    /// State of the window, panics if the window was closed
+    pub fn window(&self, handle: isize) -> FakeWindowState {
+        self.state.borrow().window(handle).unwrap().clone()
+    }
+
+    /// Calls made since the desktop was created or the calls were last taken
+    pub fn take_calls(&self) -> Vec<FakeCall> {
+        std::mem::take(&mut self.state.borrow_mut().calls)
+    }
+
+    fn record(&self, call: FakeCall) {
+        self.state.borrow_mut().calls.push(call);
+    }
+
+    fn fake_window(&self, handle: isize) -> Box<dyn Window> {
+        Box::new(FakeWindow {
+            state: self.state.clone(),
+            handle,
+        })
+    }
+}
+
+impl Desktop for FakeDesktop {
+    fn get_foreground_window(&self) -> Result<Box<dyn Window>> {
+        self.record(FakeCall::GetForegroundWindow);
+        let handle = self
+            .state
+            .borrow()
+            .foreground_window
+            .ok_or(Error::NoForegroundWindow)?;
+        Ok(self.fake_window(handle))
+    }
+
+    fn get_all_monitors(&self) -> Result<Vec<Box<dyn Monitor>>> {
+        self.record(FakeCall::GetAllMonitors);
+        Ok(self
+            .state
+            .borrow()
+            .monitors
+            .iter()
+            .map(|monitor| Box::new(monitor.clone()) as Box<dyn Monitor>)
+            .collect())
+    }
+
+    /// All windows that aren't minimized
+    fn get_all_windows(&self) -> Result<Vec<Box<dyn Window>>> {
+        self.record(FakeCall::GetAllWindows);
+        let handles: Vec<isize> = self
+            .state
+            .borrow()
+            .windows
+            .iter()
+            .filter(|window| window.state != WindowState::Minimized)
+            .map(|window| window.handle)
+            .collect();
+        Ok(handles
+            .into_iter()
+            .map(|handle| self.fake_window(handle))
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rect(left: i32, top: i32, width: i32, height: i32) -> Rect {
+        Rect {
+            left,
+            top,
+            right: left + width,
+            bottom: top + height,
+        }
+    }
+
+    #[test]
+    fn maximizes_to_the_monitor_and_restores() {
+        let desktop = FakeDesktop::with_monitors([
+            FakeMonitor::new(1, rect(0, 0, 1920, 1040)),
+            FakeMonitor::new(2, rect(1920, 0, 2560, 1400)),
+        ]);
+        let handle = desktop.add_window(rect(2000, 100, 800, 600));
+        desktop.set_window_margin(
+            handle,
+            WindowBorderSize {
+                left: -7,
+                right: 7,
+                top: 0,
+                bottom: 7,
+            },
+        );
+
+        let window = desktop.get_foreground_window().unwrap();
+        assert_eq!(
+            window
+                .get_current_monitor()
+                .unwrap()
+                .get_platform_specific_handle(),
+            2
+        );
+        window.maximize().unwrap();
+        assert_eq!(desktop.window(handle).state, WindowState::Maximized);
+        assert_eq!(desktop.window(handle).rect, rect(1913, 0, 2574, 1407));
+
+        window.minimize().unwrap();
+        window.restore().unwrap();
+        assert_eq!(desktop.window(handle).state, WindowState::Maximized);
+        window.restore().unwrap();
+        assert_eq!(desktop.window(handle).state, WindowState::Normal);
+        assert_eq!(desktop.window(handle).rect, rect(2000, 100, 800, 600));
+
+        assert_eq!(
+            desktop.take_calls(),
+            vec![
+                FakeCall::GetForegroundWindow,
+                FakeCall::GetCurrentMonitor { window: handle },
+                FakeCall::Maximize { window: handle },
+                FakeCall::Minimize { window: handle },
+                FakeCall::Restore { window: handle },
+                FakeCall::Restore { window: handle },
+            ]
+        );
+        assert!(desktop.take_calls().is_empty());
+    }
+
+    #[test]
+    fn calls_to_a_closed_window_fail() {
+        let (desktop, handle) = FakeDesktop::with_monitor_and_window();
+        let window = desktop.get_foreground_window().unwrap();
+
+        desktop.close_window(handle);
+        assert_eq!(window.get_position(), Err(Error::WindowNotFound(handle)));
+        assert_eq!(
+            window.move_window(&rect(0, 0, 960, 1040)),
+            Err(Error::WindowNotFound(handle))
+        );
+        assert_eq!(
+            desktop.get_foreground_window().err(),
+            Some(Error::NoForegroundWindow)
+        );
+    }
+
+    #[test]
+    fn handles_of_closed_windows_are_not_reused() {
+        let (desktop, first) = FakeDesktop::with_monitor_and_window();
+        let second = desktop.add_window(rect(200, 200, 800, 600));
+        desktop.close_window(first);
+
+        let third = desktop.add_window(rect(300, 300, 800, 600));
+        assert_ne!(third, first);
+        assert_ne!(third, second);
+        assert_eq!(desktop.window(second).rect, rect(200, 200, 800, 600));
+        assert_eq!(desktop.window(third).rect, rect(300, 300, 800, 600));
+    }
+}
@@ -0,0 +1,84 @@
+use std::cell::RefCell;
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+
+use crate::common::enums::HandlerEvent;
+use crate::common::hotkey::{Hotkey, HotkeyRegistration, HotkeyRegistrationStatus};
+use crate::common::structs::HotkeyMapping;
+use crate::common::traits::HotkeyHandler;
+
+/// Hotkey handler returning queued events. Once the queue is empty it returns
+/// `HandlerEvent::Quit`, so a main loop run with it always ends.
+#[derive(Default)]
+pub struct FakeHotkeyHandler {
+    // Shared with the notifiers, which may be called from other threads
+    events: Arc<Mutex<VecDeque<HandlerEvent>>>,
+    registered_hotkeys: RefCell<Vec<HotkeyMapping>>,
+    // Hotkeys that fail to register, like ones already taken by another application
+    taken_hotkeys: RefCell<Vec<Hotkey>>,
+}
+
+impl FakeHotkeyHandler {
+    pub fn new(events: Vec<HandlerEvent>) -> Self {
+        Self {
+            events: Arc::new(Mutex::new(events.into())),
+            ..Self::default()
+        }
+    }
+
+    /// Makes registering `hotkey` fail from now on
+    pub fn take_hotkey(&self, hotkey: Hotkey) {
+        self.taken_hotkeys.borrow_mut().push(hotkey);
+    }
+
+    /// Hotkeys registered and not unregistered since
+    pub fn registered_hotkeys(&self) -> Vec<HotkeyMapping> {
+        self.registered_hotkeys.borrow().clone()
+    }
+
+    fn get_sender(&self, event: HandlerEvent) -> Box<dyn Fn() + Send> {
+        let events = self.events.clone();
+        Box::new(move || events.lock().unwrap().push_back(event.clone()))
+    }
+}
+
+impl HotkeyHandler for FakeHotkeyHandler {
+    /// Every hotkey that isn't taken is registered successfully
+    fn register_hotkeys(&self, keys: Vec<HotkeyMapping>) -> Vec<HotkeyRegistration> {
+        keys.into_iter()
+            .map(|mapping| {
+                if self.taken_hotkeys.borrow().contains(&mapping.hotkey) {
+                    return HotkeyRegistration {
+                        mapping,
+                        status: HotkeyRegistrationStatus::Failed("already taken".to_string()),
+                    };
+                }
+                self.registered_hotkeys.borrow_mut().push(mapping.clone());
+                HotkeyRegistration {
+                    mapping,
+                    status: HotkeyRegistrationStatus::Registered,
+                }
+            })
+            .collect()
+    }
+
+    fn unregister_hotkeys(&self) {
+        self.registered_hotkeys.borrow_mut().clear();
+    }
+
+    fn get_next_event(&self) -> HandlerEvent {
+        self.events
+            .lock()
+            .unwrap()
+            .pop_front()
+            .unwrap_or(HandlerEvent::Quit)
+    }
+
+    fn get_config_change_notifier(&self) -> Box<dyn Fn() + Send> {
+        self.get_sender(HandlerEvent::ConfigChanged)
+    }
+
+    fn get_quit_notifier(&self) -> Box<dyn Fn() + Send> {
+        self.get_sender(HandlerEvent::Quit)
+    }
+}
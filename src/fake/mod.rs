@@ -0,0 +1,10 @@
+//! In-memory backend for simulations and tests, running the actions on any OS.
+//!
+//! `FakeDesktop` holds the monitors and windows, the windows and monitors it returns share its
+//! state, so moving a window returned by `get_foreground_window` is seen through the desktop.
+//! `FakeHotkeyHandler` feeds queued events to the main loop.
+
+pub mod desktop;
+pub mod hotkey_handler;
+pub mod monitor;
+pub mod window;
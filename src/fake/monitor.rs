@@ -0,0 +1,53 @@
+use crate::common::structs::{DpiInfo, Rect, WindowBorderSize};
+use crate::common::traits::Monitor;
+use crate::Result;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct FakeMonitor {
+    pub handle: isize,
+    /// Area windows can be placed in, what `get_size` returns
+    pub work_area: Rect,
+    pub dpi: DpiInfo,
+    /// Margin of the windows on this monitor, overriding their own. On Windows the invisible
+    /// borders scale with the DPI of the monitor.
+    pub window_margin: Option<WindowBorderSize>,
+}
+
+impl FakeMonitor {
+    /// Monitor with a 96 DPI
+    pub fn new(handle: isize, work_area: Rect) -> Self {
+        Self {
+            handle,
+            work_area,
+            dpi: DpiInfo { x: 96, y: 96 },
+            window_margin: None,
+        }
+    }
+
+    /// 1920x1080 monitor with its top left corner at `left`, `top`
+    pub fn full_hd_at(handle: isize, left: i32, top: i32) -> Self {
+        Self::new(
+            handle,
+            Rect {
+                left,
+                right: left + 1920,
+                top,
+                bottom: top + 1080,
+            },
+        )
+    }
+}
+
+impl Monitor for FakeMonitor {
+    fn get_size(&self) -> Result<Rect> {
+        Ok(self.work_area.clone())
+    }
+
+    fn get_dpi_info(&self) -> Result<DpiInfo> {
+        Ok(self.dpi)
+    }
+
+    fn get_platform_specific_handle(&self) -> isize {
+        self.handle
+    }
+}
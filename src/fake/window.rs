@@ -0,0 +1,182 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use crate::common::calculation::calculate_resized_window_rect::{
+    get_visible_rect, get_window_rect_from_visible,
+};
+use crate::common::enums::WindowState;
+use crate::common::monitor_topology::find_nearest_monitor;
+use crate::common::structs::{Rect, WindowBorderSize};
+use crate::common::traits::{Monitor, Window};
+use crate::{Error, Result};
+
+use super::desktop::{FakeCall, FakeDesktopState};
+use super::monitor::FakeMonitor;
+
+/// State of a window on a `FakeDesktop`
+#[derive(Debug, Clone, PartialEq)]
+pub struct FakeWindowState {
+    pub handle: isize,
+    /// Position including the invisible margins, what `get_position` returns
+    pub rect: Rect,
+    pub state: WindowState,
+    pub margin: WindowBorderSize,
+    // Position a maximized window is restored to
+    restore_rect: Option<Rect>,
+    // State a minimized window is restored to
+    restore_state: WindowState,
+}
+
+impl FakeWindowState {
+    pub(crate) fn new(handle: isize, rect: Rect) -> Self {
+        Self {
+            handle,
+            rect,
+            state: WindowState::Normal,
+            margin: WindowBorderSize {
+                left: 0,
+                right: 0,
+                top: 0,
+                bottom: 0,
+            },
+            restore_rect: None,
+            restore_state: WindowState::Normal,
+        }
+    }
+
+    fn unmaximize(&mut self) {
+        if let Some(rect) = self.restore_rect.take() {
+            self.rect = rect;
+        }
+    }
+}
+
+/// Window on a `FakeDesktop`. Maximizing fills the work area of the window's monitor, restoring
+/// returns the window to its previous position or state.
+pub struct FakeWindow {
+    pub(crate) state: Rc<RefCell<FakeDesktopState>>,
+    pub handle: isize,
+}
+
+impl FakeWindow {
+    fn record(&self, call: FakeCall) {
+        self.state.borrow_mut().calls.push(call);
+    }
+
+    fn update<T>(&self, update: impl FnOnce(&mut FakeWindowState) -> T) -> Result<T> {
+        Ok(update(self.state.borrow_mut().window_mut(self.handle)?))
+    }
+
+    fn find_monitor(&self) -> Result<FakeMonitor> {
+        let state = self.state.borrow();
+        let window = state.window(self.handle)?;
+        let work_areas: Vec<Rect> = state
+            .monitors
+            .iter()
+            .map(|monitor| monitor.work_area.clone())
+            .collect();
+        let visible_rect = get_visible_rect(&window.rect, &window.margin);
+        let index = find_nearest_monitor(&work_areas, &visible_rect).ok_or(Error::NoMonitors)?;
+        Ok(state.monitors[index].clone())
+    }
+
+    // The margin of the monitor the window is on, if it has one, otherwise the window's own
+    fn margin(&self) -> Result<WindowBorderSize> {
+        let monitor_margin = self.find_monitor()?.window_margin;
+        self.update(|window| monitor_margin.unwrap_or_else(|| window.margin.clone()))
+    }
+}
+
+impl Window for FakeWindow {
+    fn move_window(&self, windows_rect: &Rect) -> Result<()> {
+        self.record(FakeCall::MoveWindow {
+            window: self.handle,
+            rect: windows_rect.clone(),
+        });
+        self.update(|window| window.rect = windows_rect.clone())
+    }
+
+    fn get_position(&self) -> Result<Rect> {
+        self.record(FakeCall::GetPosition {
+            window: self.handle,
+        });
+        self.update(|window| window.rect.clone())
+    }
+
+    fn get_state(&self) -> Result<WindowState> {
+        self.record(FakeCall::GetState {
+            window: self.handle,
+        });
+        self.update(|window| window.state)
+    }
+
+    fn restore(&self) -> Result<()> {
+        self.record(FakeCall::Restore {
+            window: self.handle,
+        });
+        self.update(|window| match window.state {
+            WindowState::Minimized => window.state = window.restore_state,
+            WindowState::Maximized => {
+                window.unmaximize();
+                window.state = WindowState::Normal;
+            }
+            _ => (),
+        })
+    }
+
+    fn minimize(&self) -> Result<()> {
+        self.record(FakeCall::Minimize {
+            window: self.handle,
+        });
+        self.update(|window| {
+            if window.state != WindowState::Minimized {
+                window.restore_state = window.state;
+                window.state = WindowState::Minimized;
+            }
+        })
+    }
+
+    fn maximize(&self) -> Result<()> {
+        self.record(FakeCall::Maximize {
+            window: self.handle,
+        });
+        let work_area = self.find_monitor()?.get_size()?;
+        let margin = self.margin()?;
+        self.update(|window| {
+            if window.state != WindowState::Maximized {
+                window.restore_rect = Some(window.rect.clone());
+            }
+            window.rect = get_window_rect_from_visible(&work_area, &margin);
+            window.state = WindowState::Maximized;
+        })
+    }
+
+    fn disable_snapping(&self) -> Result<()> {
+        self.record(FakeCall::DisableSnapping {
+            window: self.handle,
+        });
+        // Like showing the window normally on Windows
+        self.update(|window| {
+            window.unmaximize();
+            window.state = WindowState::Normal;
+        })
+    }
+
+    fn get_margin(&self) -> Result<WindowBorderSize> {
+        self.record(FakeCall::GetMargin {
+            window: self.handle,
+        });
+        self.margin()
+    }
+
+    fn get_current_monitor(&self) -> Result<Box<dyn Monitor>> {
+        self.record(FakeCall::GetCurrentMonitor {
+            window: self.handle,
+        });
+        Ok(Box::new(self.find_monitor()?))
+    }
+
+    fn get_platform_specific_handle(&self) -> isize {
+        self.handle
+    }
+}
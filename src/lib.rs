@@ -1,5 +1,9 @@
 pub mod common;
+pub mod error;
+pub mod fake;
+#[cfg(target_os = "linux")]
+pub mod linux;
+#[cfg(windows)]
 pub mod windows;
 
-#[macro_use]
-extern crate enum_primitive;
+pub use error::{Error, Result};
@@ -0,0 +1,169 @@
+use log::{error, info};
+
+use x11rb::connection::Connection;
+use x11rb::errors::ReplyError;
+use x11rb::protocol::randr::ConnectionExt as _;
+use x11rb::protocol::xproto::{
+    AtomEnum, ClientMessageEvent, ConnectionExt as _, EventMask, Window as XWindow,
+};
+use x11rb::rust_connection::RustConnection;
+
+use crate::common::structs::{DpiInfo, Rect};
+use crate::{Error, Result};
+
+use super::monitor::{calculate_dpi, X11Monitor};
+
+x11rb::atom_manager! {
+    pub(crate) Atoms: AtomsCookie {
+        _NET_ACTIVE_WINDOW,
+        _NET_CLIENT_LIST,
+        _NET_CURRENT_DESKTOP,
+        _NET_FRAME_EXTENTS,
+        _NET_MOVERESIZE_WINDOW,
+        _NET_WM_STATE,
+        _NET_WM_STATE_HIDDEN,
+        _NET_WM_STATE_MAXIMIZED_HORZ,
+        _NET_WM_STATE_MAXIMIZED_VERT,
+        _NET_WORKAREA,
+        WM_CHANGE_STATE,
+    }
+}
+
+/// Connection to the X server shared by the desktop, its windows and monitors
+pub(crate) struct X11Connection {
+    pub conn: RustConnection,
+    pub root: XWindow,
+    pub atoms: Atoms,
+}
+
+impl X11Connection {
+    /// Connects to the display given in `DISPLAY`
+    pub fn connect() -> Result<Self> {
+        let (conn, screen_num) =
+            x11rb::connect(None).map_err(|error| Error::Connection(error.to_string()))?;
+        let root = conn.setup().roots[screen_num].root;
+        let atoms = Atoms::new(&conn)
+            .map_err(ReplyError::from)
+            .and_then(|cookie| cookie.reply())
+            .map_err(|error| Error::os("InternAtom", error))?;
+        Ok(Self { conn, root, atoms })
+    }
+
+    /// Values of a 32-bit property, empty if the window doesn't have the property
+    pub fn get_property32(
+        &self,
+        window: XWindow,
+        property: u32,
+        type_: AtomEnum,
+    ) -> Result<Vec<u32>> {
+        let reply = self
+            .conn
+            .get_property(false, window, property, type_, 0, u32::MAX / 4)
+            .map_err(ReplyError::from)
+            .and_then(|cookie| cookie.reply())
+            .map_err(|error| Error::os("GetProperty", error))?;
+        Ok(reply
+            .value32()
+            .map(|values| values.collect())
+            .unwrap_or_default())
+    }
+
+    /// Sends an EWMH client message about `window` to the window manager
+    pub fn send_client_message(
+        &self,
+        window: XWindow,
+        message_type: u32,
+        data: [u32; 5],
+    ) -> Result<()> {
+        info!(
+            "send_client_message: window={}, type={}, data={:?}",
+            window, message_type, data
+        );
+        let event = ClientMessageEvent::new(32, window, message_type, data);
+        self.conn
+            .send_event(
+                false,
+                self.root,
+                EventMask::SUBSTRUCTURE_REDIRECT | EventMask::SUBSTRUCTURE_NOTIFY,
+                event,
+            )
+            .and_then(|_| self.conn.flush())
+            .map_err(|error| Error::os("SendEvent", error))
+    }
+
+    /// Work area of the current desktop from `_NET_WORKAREA`, spanning all monitors. `None` if
+    /// the window manager doesn't set it.
+    pub fn get_work_area(&self) -> Result<Option<Rect>> {
+        let desktop = self
+            .get_property32(
+                self.root,
+                self.atoms._NET_CURRENT_DESKTOP,
+                AtomEnum::CARDINAL,
+            )?
+            .first()
+            .copied()
+            .unwrap_or(0) as usize;
+        let work_areas =
+            self.get_property32(self.root, self.atoms._NET_WORKAREA, AtomEnum::CARDINAL)?;
+        Ok(work_areas.chunks_exact(4).nth(desktop).map(|area| Rect {
+            left: area[0] as i32,
+            top: area[1] as i32,
+            right: (area[0] + area[2]) as i32,
+            bottom: (area[1] + area[3]) as i32,
+        }))
+    }
+
+    /// Active monitors from RandR. Falls back to a single monitor covering the screen if RandR
+    /// isn't available.
+    pub fn get_monitors(&self) -> Result<Vec<X11Monitor>> {
+        let work_area = self.get_work_area()?;
+        let monitors = self
+            .conn
+            .randr_get_monitors(self.root, true)
+            .map_err(|error| error.to_string())
+            .and_then(|cookie| cookie.reply().map_err(|error| error.to_string()));
+
+        Ok(match monitors {
+            Ok(reply) if !reply.monitors.is_empty() => reply
+                .monitors
+                .iter()
+                .map(|monitor| {
+                    let bounds = Rect {
+                        left: monitor.x.into(),
+                        top: monitor.y.into(),
+                        right: i32::from(monitor.x) + i32::from(monitor.width),
+                        bottom: i32::from(monitor.y) + i32::from(monitor.height),
+                    };
+                    let dpi = DpiInfo {
+                        x: calculate_dpi(monitor.width, monitor.width_in_millimeters),
+                        y: calculate_dpi(monitor.height, monitor.height_in_millimeters),
+                    };
+                    X11Monitor::new(monitor.name, &bounds, work_area.as_ref(), dpi)
+                })
+                .collect(),
+            result => {
+                if let Err(error) = result {
+                    error!("get_monitors: RandR: {}", error);
+                }
+                let screen = self
+                    .conn
+                    .setup()
+                    .roots
+                    .iter()
+                    .find(|screen| screen.root == self.root)
+                    .expect("root window belongs to a screen");
+                let bounds = Rect {
+                    left: 0,
+                    top: 0,
+                    right: screen.width_in_pixels.into(),
+                    bottom: screen.height_in_pixels.into(),
+                };
+                let dpi = DpiInfo {
+                    x: calculate_dpi(screen.width_in_pixels, screen.width_in_millimeters.into()),
+                    y: calculate_dpi(screen.height_in_pixels, screen.height_in_millimeters.into()),
+                };
+                vec![X11Monitor::new(0, &bounds, work_area.as_ref(), dpi)]
+            }
+        })
+    }
+}
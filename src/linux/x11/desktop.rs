@@ -0,0 +1,96 @@
+use std::rc::Rc;
+
+use x11rb::errors::ReplyError;
+use x11rb::protocol::xproto::{AtomEnum, ConnectionExt as _, InputFocus};
+
+use crate::common::traits::{Desktop, Monitor, Window};
+use crate::{Error, Result};
+
+use super::connection::X11Connection;
+use super::window::X11Window;
+
+pub struct X11Desktop {
+    connection: Rc<X11Connection>,
+}
+
+impl X11Desktop {
+    /// Connects to the X server given in `DISPLAY`. The window manager has to support EWMH.
+    pub fn new() -> Result<Self> {
+        Ok(Self {
+            connection: Rc::new(X11Connection::connect()?),
+        })
+    }
+
+    fn window(&self, window: u32) -> X11Window {
+        X11Window {
+            connection: self.connection.clone(),
+            platform_specific_handle: window,
+        }
+    }
+}
+
+impl Desktop for X11Desktop {
+    fn get_foreground_window(&self) -> Result<Box<dyn Window>> {
+        let connection = &self.connection;
+        let active = connection
+            .get_property32(
+                connection.root,
+                connection.atoms._NET_ACTIVE_WINDOW,
+                AtomEnum::WINDOW,
+            )?
+            .first()
+            .copied()
+            .filter(|window| *window != 0);
+
+        // Without an EWMH window manager, the window with the input focus
+        let window = match active {
+            Some(window) => window,
+            None => {
+                connection
+                    .conn
+                    .get_input_focus()
+                    .map_err(ReplyError::from)
+                    .and_then(|cookie| cookie.reply())
+                    .map_err(|error| Error::os("GetInputFocus", error))?
+                    .focus
+            }
+        };
+        if window == connection.root
+            || window == u32::from(InputFocus::NONE)
+            || window == u32::from(InputFocus::POINTER_ROOT)
+        {
+            return Err(Error::NoForegroundWindow);
+        }
+        Ok(Box::new(self.window(window)))
+    }
+
+    fn get_all_monitors(&self) -> Result<Vec<Box<dyn Monitor>>> {
+        Ok(self
+            .connection
+            .get_monitors()?
+            .into_iter()
+            .map(|monitor| Box::new(monitor) as Box<dyn Monitor>)
+            .collect())
+    }
+
+    fn get_all_windows(&self) -> Result<Vec<Box<dyn Window>>> {
+        let connection = &self.connection;
+        let mut windows: Vec<Box<dyn Window>> = Vec::new();
+        for window in connection.get_property32(
+            connection.root,
+            connection.atoms._NET_CLIENT_LIST,
+            AtomEnum::WINDOW,
+        )? {
+            // Minimized windows aren't visible. Windows closed since the list was read are left
+            // out as well.
+            let hidden = connection
+                .get_property32(window, connection.atoms._NET_WM_STATE, AtomEnum::ATOM)
+                .map(|state| state.contains(&connection.atoms._NET_WM_STATE_HIDDEN))
+                .unwrap_or(true);
+            if !hidden {
+                windows.push(Box::new(self.window(window)));
+            }
+        }
+        Ok(windows)
+    }
+}
@@ -0,0 +1,412 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use x11rb::connection::Connection;
+use x11rb::errors::ReplyError;
+use x11rb::protocol::xproto::{
+    ClientMessageEvent, ConnectionExt as _, CreateWindowAux, EventMask, GrabMode, Keycode, Keysym,
+    ModMask, Window as XWindow, WindowClass,
+};
+use x11rb::protocol::{ErrorKind, Event};
+use x11rb::rust_connection::RustConnection;
+use x11rb::COPY_FROM_PARENT;
+
+use crate::common::{
+    enums::{HandlerEvent, HotKeyButton},
+    hotkey::{
+        find_duplicate_hotkeys, HotKeyModifiers, HotkeyRegistration, HotkeyRegistrationStatus,
+    },
+    structs::HotkeyMapping,
+    traits::HotkeyHandler,
+};
+use crate::Error;
+
+// Caps Lock and Num Lock (Mod2 on practically every keyboard layout) don't change the meaning of a
+// hotkey, so each hotkey is grabbed with every combination of them
+const LOCK_MODIFIERS: [u16; 4] = [0, LOCK_MASK, NUM_LOCK_MASK, LOCK_MASK | NUM_LOCK_MASK];
+const LOCK_MASK: u16 = 1 << 1;
+const NUM_LOCK_MASK: u16 = 1 << 4;
+
+// Names of the client messages sent when the configuration file changes and to stop the main loop
+const CONFIG_CHANGED_MESSAGE: &[u8] = b"_WIN_MOVE_CONFIG_CHANGED";
+const QUIT_MESSAGE: &[u8] = b"_WIN_MOVE_QUIT";
+
+pub struct X11HotkeyHandler {
+    // Shared with the config change notifier, which runs on another thread
+    connection: Arc<RustConnection>,
+    root: XWindow,
+    // Hidden window receiving the config changed and quit messages
+    message_window: XWindow,
+    config_changed_atom: u32,
+    quit_atom: u32,
+    // Grabbed hotkeys by keycode and modifier mask, without the lock modifiers
+    registered_hotkeys: RefCell<HashMap<(Keycode, u16), HotkeyMapping>>,
+}
+
+impl X11HotkeyHandler {
+    /// Connects to the X server given in `DISPLAY`
+    pub fn new() -> Result<Self, Error> {
+        let (conn, screen_num) =
+            x11rb::connect(None).map_err(|error| Error::Connection(error.to_string()))?;
+        let root = conn.setup().roots[screen_num].root;
+
+        let message_window = conn
+            .generate_id()
+            .map_err(|error| Error::os("GenerateId", error))?;
+        conn.create_window(
+            COPY_FROM_PARENT as u8,
+            message_window,
+            root,
+            0,
+            0,
+            1,
+            1,
+            0,
+            WindowClass::INPUT_ONLY,
+            COPY_FROM_PARENT,
+            &CreateWindowAux::new(),
+        )
+        .map_err(ReplyError::from)
+        .and_then(|cookie| cookie.check())
+        .map_err(|error| Error::os("CreateWindow", error))?;
+        let intern_atom = |name| {
+            conn.intern_atom(false, name)
+                .map_err(ReplyError::from)
+                .and_then(|cookie| Ok(cookie.reply()?.atom))
+                .map_err(|error: ReplyError| Error::os("InternAtom", error))
+        };
+        let config_changed_atom = intern_atom(CONFIG_CHANGED_MESSAGE)?;
+        let quit_atom = intern_atom(QUIT_MESSAGE)?;
+
+        Ok(Self {
+            connection: Arc::new(conn),
+            root,
+            message_window,
+            config_changed_atom,
+            quit_atom,
+            registered_hotkeys: RefCell::new(HashMap::new()),
+        })
+    }
+
+    // Keycodes by the keysyms they produce, on any shift level
+    fn get_keycodes(&self) -> Result<HashMap<Keysym, Keycode>, ReplyError> {
+        let setup = self.connection.setup();
+        let (min_keycode, max_keycode) = (setup.min_keycode, setup.max_keycode);
+        let mapping = self
+            .connection
+            .get_keyboard_mapping(min_keycode, max_keycode - min_keycode + 1)?
+            .reply()?;
+
+        let mut keycodes = HashMap::new();
+        let keysyms_per_keycode = usize::from(mapping.keysyms_per_keycode).max(1);
+        for (keycode, keysyms) in
+            (min_keycode..=max_keycode).zip(mapping.keysyms.chunks(keysyms_per_keycode))
+        {
+            for keysym in keysyms {
+                keycodes.entry(*keysym).or_insert(keycode);
+            }
+        }
+        Ok(keycodes)
+    }
+
+    fn grab_hotkey(&self, keycode: Keycode, modifiers: u16) -> Result<(), String> {
+        for (grabbed, lock_modifiers) in LOCK_MODIFIERS.iter().enumerate() {
+            let result = self
+                .connection
+                .grab_key(
+                    false,
+                    self.root,
+                    ModMask::from(modifiers | lock_modifiers),
+                    keycode,
+                    GrabMode::ASYNC,
+                    GrabMode::ASYNC,
+                )
+                .map_err(ReplyError::from)
+                .and_then(|cookie| cookie.check());
+            if let Err(error) = result {
+                for lock_modifiers in &LOCK_MODIFIERS[..grabbed] {
+                    self.ungrab_hotkey(keycode, modifiers | lock_modifiers);
+                }
+                return Err(match error {
+                    ReplyError::X11Error(error) if error.error_kind == ErrorKind::Access => {
+                        "already grabbed by another application".to_string()
+                    }
+                    error => error.to_string(),
+                });
+            }
+        }
+        Ok(())
+    }
+
+    fn ungrab_hotkey(&self, keycode: Keycode, modifiers: u16) {
+        let result = self
+            .connection
+            .ungrab_key(keycode, self.root, ModMask::from(modifiers))
+            .map_err(ReplyError::from)
+            .and_then(|cookie| cookie.check());
+        if let Err(error) = result {
+            log::warn!("Failed to ungrab keycode {}: {}", keycode, error);
+        }
+    }
+
+    // Function sending the client message `atom` to the message window from any thread
+    fn get_message_sender(&self, atom: u32) -> Box<dyn Fn() + Send> {
+        let connection = self.connection.clone();
+        let window = self.message_window;
+        Box::new(move || {
+            // Without an event mask, the message goes to the client that created the window
+            let event = ClientMessageEvent::new(32, window, atom, [0u32; 5]);
+            let sent = connection
+                .send_event(false, window, EventMask::NO_EVENT, event)
+                .and_then(|_| connection.flush());
+            if let Err(error) = sent {
+                log::warn!("Failed to send a message to the main loop: {}", error);
+            }
+        })
+    }
+}
+
+impl HotkeyHandler for X11HotkeyHandler {
+    fn register_hotkeys(&self, keys: Vec<HotkeyMapping>) -> Vec<HotkeyRegistration> {
+        let keycodes = self.get_keycodes().unwrap_or_else(|error| {
+            log::error!("Couldn't read the keyboard mapping: {}", error);
+            HashMap::new()
+        });
+        let duplicates = find_duplicate_hotkeys(&keys);
+
+        keys.into_iter()
+            .zip(duplicates)
+            .map(|(mapping, duplicate)| {
+                let keysym = map_button_to_keysym(&mapping.hotkey.key);
+                let modifiers = map_modifiers_to_mod_mask(&mapping.hotkey.modifiers);
+                let status = match (duplicate, keycodes.get(&keysym)) {
+                    (Some(previous), _) => HotkeyRegistrationStatus::Duplicate { previous },
+                    (None, None) => HotkeyRegistrationStatus::Failed(
+                        "no key on the keyboard produces it".to_string(),
+                    ),
+                    (None, Some(&keycode)) => match self.grab_hotkey(keycode, modifiers) {
+                        Ok(()) => {
+                            self.registered_hotkeys
+                                .borrow_mut()
+                                .insert((keycode, modifiers), mapping.clone());
+                            HotkeyRegistrationStatus::Registered
+                        }
+                        Err(reason) => HotkeyRegistrationStatus::Failed(reason),
+                    },
+                };
+                HotkeyRegistration { mapping, status }
+            })
+            .collect()
+    }
+
+    fn unregister_hotkeys(&self) {
+        for ((keycode, modifiers), _) in self.registered_hotkeys.borrow_mut().drain() {
+            for lock_modifiers in LOCK_MODIFIERS {
+                self.ungrab_hotkey(keycode, modifiers | lock_modifiers);
+            }
+        }
+    }
+
+    fn get_next_event(&self) -> HandlerEvent {
+        let modifier_mask = map_modifiers_to_mod_mask(&HotKeyModifiers {
+            control: true,
+            alt: true,
+            shift: true,
+            super_key: true,
+        });
+        let event = match self.connection.wait_for_event() {
+            Ok(event) => event,
+            Err(error) => {
+                log::error!("Lost the connection to the X server: {}", error);
+                return HandlerEvent::Quit;
+            }
+        };
+        match event {
+            Event::KeyPress(event) => {
+                let modifiers = u16::from(event.state) & modifier_mask;
+                let mapping = self
+                    .registered_hotkeys
+                    .borrow()
+                    .get(&(event.detail, modifiers))
+                    .cloned();
+                match mapping {
+                    Some(mapping) => HandlerEvent::Hotkey(mapping.action),
+                    None => {
+                        log::warn!(
+                            "Received unknown hotkey: keycode {}, modifiers {:#x}",
+                            event.detail,
+                            modifiers
+                        );
+                        HandlerEvent::Ignored
+                    }
+                }
+            }
+            Event::ClientMessage(event) if event.type_ == self.config_changed_atom => {
+                HandlerEvent::ConfigChanged
+            }
+            Event::ClientMessage(event) if event.type_ == self.quit_atom => HandlerEvent::Quit,
+            _ => HandlerEvent::Ignored,
+        }
+    }
+
+    fn get_config_change_notifier(&self) -> Box<dyn Fn() + Send> {
+        self.get_message_sender(self.config_changed_atom)
+    }
+
+    fn get_quit_notifier(&self) -> Box<dyn Fn() + Send> {
+        self.get_message_sender(self.quit_atom)
+    }
+}
+
+fn map_button_to_keysym(button: &HotKeyButton) -> Keysym {
+    match button {
+        HotKeyButton::VkA => 0x0061,
+        HotKeyButton::VkB => 0x0062,
+        HotKeyButton::VkC => 0x0063,
+        HotKeyButton::VkD => 0x0064,
+        HotKeyButton::VkE => 0x0065,
+        HotKeyButton::VkF => 0x0066,
+        HotKeyButton::VkG => 0x0067,
+        HotKeyButton::VkH => 0x0068,
+        HotKeyButton::VkI => 0x0069,
+        HotKeyButton::VkJ => 0x006a,
+        HotKeyButton::VkK => 0x006b,
+        HotKeyButton::VkL => 0x006c,
+        HotKeyButton::VkM => 0x006d,
+        HotKeyButton::VkN => 0x006e,
+        HotKeyButton::VkO => 0x006f,
+        HotKeyButton::VkP => 0x0070,
+        HotKeyButton::VkQ => 0x0071,
+        HotKeyButton::VkR => 0x0072,
+        HotKeyButton::VkS => 0x0073,
+        HotKeyButton::VkT => 0x0074,
+        HotKeyButton::VkU => 0x0075,
+        HotKeyButton::VkV => 0x0076,
+        HotKeyButton::VkW => 0x0077,
+        HotKeyButton::VkX => 0x0078,
+        HotKeyButton::VkY => 0x0079,
+        HotKeyButton::VkZ => 0x007a,
+        HotKeyButton::Vk0 => 0x0030,
+        HotKeyButton::Vk1 => 0x0031,
+        HotKeyButton::Vk2 => 0x0032,
+        HotKeyButton::Vk3 => 0x0033,
+        HotKeyButton::Vk4 => 0x0034,
+        HotKeyButton::Vk5 => 0x0035,
+        HotKeyButton::Vk6 => 0x0036,
+        HotKeyButton::Vk7 => 0x0037,
+        HotKeyButton::Vk8 => 0x0038,
+        HotKeyButton::Vk9 => 0x0039,
+        HotKeyButton::VkNumpad0 => 0xffb0,
+        HotKeyButton::VkNumpad1 => 0xffb1,
+        HotKeyButton::VkNumpad2 => 0xffb2,
+        HotKeyButton::VkNumpad3 => 0xffb3,
+        HotKeyButton::VkNumpad4 => 0xffb4,
+        HotKeyButton::VkNumpad5 => 0xffb5,
+        HotKeyButton::VkNumpad6 => 0xffb6,
+        HotKeyButton::VkNumpad7 => 0xffb7,
+        HotKeyButton::VkNumpad8 => 0xffb8,
+        HotKeyButton::VkNumpad9 => 0xffb9,
+        HotKeyButton::VkDecimal => 0xffae,
+        HotKeyButton::VkMultiply => 0xffaa,
+        HotKeyButton::VkAdd => 0xffab,
+        HotKeyButton::VkSubtract => 0xffad,
+        HotKeyButton::VkDivide => 0xffaf,
+        HotKeyButton::VkF1 => 0xffbe,
+        HotKeyButton::VkF2 => 0xffbf,
+        HotKeyButton::VkF3 => 0xffc0,
+        HotKeyButton::VkF4 => 0xffc1,
+        HotKeyButton::VkF5 => 0xffc2,
+        HotKeyButton::VkF6 => 0xffc3,
+        HotKeyButton::VkF7 => 0xffc4,
+        HotKeyButton::VkF8 => 0xffc5,
+        HotKeyButton::VkF9 => 0xffc6,
+        HotKeyButton::VkF10 => 0xffc7,
+        HotKeyButton::VkF11 => 0xffc8,
+        HotKeyButton::VkF12 => 0xffc9,
+        HotKeyButton::VkF13 => 0xffca,
+        HotKeyButton::VkF14 => 0xffcb,
+        HotKeyButton::VkF15 => 0xffcc,
+        HotKeyButton::VkF16 => 0xffcd,
+        HotKeyButton::VkF17 => 0xffce,
+        HotKeyButton::VkF18 => 0xffcf,
+        HotKeyButton::VkF19 => 0xffd0,
+        HotKeyButton::VkF20 => 0xffd1,
+        HotKeyButton::VkF21 => 0xffd2,
+        HotKeyButton::VkF22 => 0xffd3,
+        HotKeyButton::VkF23 => 0xffd4,
+        HotKeyButton::VkF24 => 0xffd5,
+        HotKeyButton::VkLeft => 0xff51,
+        HotKeyButton::VkRight => 0xff53,
+        HotKeyButton::VkUp => 0xff52,
+        HotKeyButton::VkDown => 0xff54,
+        HotKeyButton::VkHome => 0xff50,
+        HotKeyButton::VkEnd => 0xff57,
+        HotKeyButton::VkPageUp => 0xff55,
+        HotKeyButton::VkPageDown => 0xff56,
+        HotKeyButton::VkInsert => 0xff63,
+        HotKeyButton::VkDelete => 0xffff,
+        HotKeyButton::VkBackspace => 0xff08,
+        HotKeyButton::VkTab => 0xff09,
+        HotKeyButton::VkEnter => 0xff0d,
+        HotKeyButton::VkEscape => 0xff1b,
+        HotKeyButton::VkSpace => 0x0020,
+        HotKeyButton::VkComma => 0x002c,
+        HotKeyButton::VkPeriod => 0x002e,
+        HotKeyButton::VkMinus => 0x002d,
+        HotKeyButton::VkEquals => 0x003d,
+        HotKeyButton::VkSemicolon => 0x003b,
+        HotKeyButton::VkSlash => 0x002f,
+        HotKeyButton::VkBackquote => 0x0060,
+        HotKeyButton::VkLeftBracket => 0x005b,
+        HotKeyButton::VkBackslash => 0x005c,
+        HotKeyButton::VkRightBracket => 0x005d,
+        HotKeyButton::VkQuote => 0x0027,
+    }
+}
+
+fn map_modifiers_to_mod_mask(modifiers: &HotKeyModifiers) -> u16 {
+    let mut mod_mask = 0;
+    for (held, modifier) in [
+        (modifiers.control, ModMask::CONTROL),
+        (modifiers.alt, ModMask::M1),
+        (modifiers.shift, ModMask::SHIFT),
+        (modifiers.super_key, ModMask::M4),
+    ] {
+        if held {
+            mod_mask |= u16::from(modifier);
+        }
+    }
+    mod_mask
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    #[test]
+    fn maps_every_key_to_a_different_keysym() {
+        let keysyms: HashSet<Keysym> = HotKeyButton::ALL.iter().map(map_button_to_keysym).collect();
+        assert_eq!(keysyms.len(), HotKeyButton::ALL.len());
+        assert_eq!(map_button_to_keysym(&HotKeyButton::VkNumpad7), 0xffb7);
+        assert_eq!(map_button_to_keysym(&HotKeyButton::VkF24), 0xffd5);
+    }
+
+    #[test]
+    fn lock_modifiers_are_not_hotkey_modifiers() {
+        let all = map_modifiers_to_mod_mask(&HotKeyModifiers {
+            control: true,
+            alt: true,
+            shift: true,
+            super_key: true,
+        });
+        for lock_modifiers in LOCK_MODIFIERS {
+            assert_eq!(all & lock_modifiers, 0);
+        }
+        assert_eq!(
+            map_modifiers_to_mod_mask(&HotKeyModifiers::CONTROL),
+            u16::from(ModMask::CONTROL)
+        );
+    }
+}
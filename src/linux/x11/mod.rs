@@ -0,0 +1,134 @@
+pub(crate) mod connection;
+pub mod desktop;
+pub mod hotkey_handler;
+pub mod monitor;
+pub mod window;
+
+// These need an X server with an EWMH window manager, run them under Xvfb with eg.
+// `xvfb-run -a sh -c 'openbox & sleep 1; cargo test -- --ignored x11'`
+#[cfg(test)]
+mod tests {
+    use std::rc::Rc;
+    use std::thread;
+    use std::time::Duration;
+
+    use x11rb::connection::Connection;
+    use x11rb::protocol::xproto::{AtomEnum, ConnectionExt as _, CreateWindowAux, WindowClass};
+    use x11rb::COPY_FROM_PARENT;
+
+    use super::connection::X11Connection;
+    use super::hotkey_handler::X11HotkeyHandler;
+    use super::window::X11Window;
+    use crate::common::enums::WindowState;
+    use crate::common::hotkey::HotkeyRegistrationStatus;
+    use crate::common::hotkey_action::HotKeyAction;
+    use crate::common::structs::{HotkeyMapping, Rect};
+    use crate::common::traits::{HotkeyHandler, Window};
+
+    // Window managers handle requests asynchronously
+    fn wait_until(condition: impl Fn() -> bool) -> bool {
+        for _ in 0..50 {
+            if condition() {
+                return true;
+            }
+            thread::sleep(Duration::from_millis(50));
+        }
+        false
+    }
+
+    fn create_window(connection: &X11Connection) -> u32 {
+        let conn = &connection.conn;
+        let window = conn.generate_id().unwrap();
+        conn.create_window(
+            COPY_FROM_PARENT as u8,
+            window,
+            connection.root,
+            0,
+            0,
+            200,
+            100,
+            0,
+            WindowClass::INPUT_OUTPUT,
+            COPY_FROM_PARENT,
+            &CreateWindowAux::new(),
+        )
+        .unwrap();
+        conn.map_window(window).unwrap();
+        conn.flush().unwrap();
+
+        let managed = wait_until(|| {
+            connection
+                .get_property32(
+                    connection.root,
+                    connection.atoms._NET_CLIENT_LIST,
+                    AtomEnum::WINDOW,
+                )
+                .unwrap()
+                .contains(&window)
+        });
+        assert!(managed, "window manager didn't manage the window");
+        window
+    }
+
+    #[test]
+    #[ignore]
+    fn x11_moves_and_maximizes_windows() {
+        let connection = Rc::new(X11Connection::connect().unwrap());
+        let window = X11Window {
+            platform_specific_handle: create_window(&connection),
+            connection,
+        };
+
+        let target = Rect {
+            left: 100,
+            top: 150,
+            right: 500,
+            bottom: 450,
+        };
+        window.move_window(&target).unwrap();
+        assert!(wait_until(|| window.get_position() == Ok(target.clone())));
+
+        window.maximize().unwrap();
+        assert!(wait_until(
+            || window.get_state() == Ok(WindowState::Maximized)
+        ));
+        window.restore().unwrap();
+        assert!(wait_until(|| window.get_state() == Ok(WindowState::Normal)));
+
+        // Calls to a closed window fail instead of returning an empty rect
+        let conn = &window.connection.conn;
+        conn.destroy_window(window.platform_specific_handle)
+            .unwrap();
+        conn.flush().unwrap();
+        assert!(window.get_position().is_err());
+    }
+
+    #[test]
+    #[ignore]
+    fn x11_reports_hotkeys_grabbed_by_another_client() {
+        let mappings = vec![HotkeyMapping {
+            action: HotKeyAction::MaximizeWindow,
+            hotkey: "Ctrl+Alt+F12".parse().unwrap(),
+        }];
+        let first = X11HotkeyHandler::new().unwrap();
+        let second = X11HotkeyHandler::new().unwrap();
+
+        let registrations = first.register_hotkeys(mappings.clone());
+        assert_eq!(
+            registrations[0].status,
+            HotkeyRegistrationStatus::Registered
+        );
+        let registrations = second.register_hotkeys(mappings.clone());
+        assert!(matches!(
+            registrations[0].status,
+            HotkeyRegistrationStatus::Failed(_)
+        ));
+
+        first.unregister_hotkeys();
+        let registrations = second.register_hotkeys(mappings);
+        assert_eq!(
+            registrations[0].status,
+            HotkeyRegistrationStatus::Registered
+        );
+    }
+}
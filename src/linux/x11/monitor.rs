@@ -0,0 +1,106 @@
+use crate::common::structs::{DpiInfo, Rect};
+use crate::common::traits::Monitor;
+use crate::Result;
+
+// DPI assumed when the monitor doesn't report its physical size
+const DEFAULT_DPI: u32 = 96;
+
+pub struct X11Monitor {
+    /// RandR name of the monitor, eg. the atom for "DP-1"
+    pub platform_specific_handle: u32,
+    work_area: Rect,
+    dpi: DpiInfo,
+}
+
+impl X11Monitor {
+    /// `work_area` is `_NET_WORKAREA`, which spans all monitors, so it's clipped to `bounds`
+    pub(crate) fn new(name: u32, bounds: &Rect, work_area: Option<&Rect>, dpi: DpiInfo) -> Self {
+        Self {
+            platform_specific_handle: name,
+            work_area: work_area
+                .and_then(|work_area| intersect(bounds, work_area))
+                .unwrap_or_else(|| bounds.clone()),
+            dpi,
+        }
+    }
+}
+
+impl Monitor for X11Monitor {
+    fn get_size(&self) -> Result<Rect> {
+        Ok(self.work_area.clone())
+    }
+
+    fn get_dpi_info(&self) -> Result<DpiInfo> {
+        Ok(DpiInfo {
+            x: self.dpi.x,
+            y: self.dpi.y,
+        })
+    }
+
+    fn get_platform_specific_handle(&self) -> isize {
+        self.platform_specific_handle as isize
+    }
+}
+
+/// Dots per inch from the size of the monitor in pixels and millimeters
+pub(crate) fn calculate_dpi(pixels: u16, millimeters: u32) -> u32 {
+    if millimeters == 0 {
+        return DEFAULT_DPI;
+    }
+    (f64::from(pixels) * 25.4 / f64::from(millimeters)).round() as u32
+}
+
+fn intersect(a: &Rect, b: &Rect) -> Option<Rect> {
+    let rect = Rect {
+        left: a.left.max(b.left),
+        top: a.top.max(b.top),
+        right: a.right.min(b.right),
+        bottom: a.bottom.min(b.bottom),
+    };
+    (rect.width() > 0 && rect.height() > 0).then_some(rect)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rect(left: i32, top: i32, right: i32, bottom: i32) -> Rect {
+        Rect {
+            left,
+            top,
+            right,
+            bottom,
+        }
+    }
+
+    #[test]
+    fn clips_work_area_to_monitor() {
+        // Panel along the top of the left monitor only
+        let work_area = rect(0, 30, 3840, 1080);
+        let left = X11Monitor::new(
+            1,
+            &rect(0, 0, 1920, 1080),
+            Some(&work_area),
+            DpiInfo { x: 96, y: 96 },
+        );
+        let right = X11Monitor::new(
+            2,
+            &rect(1920, 0, 3840, 1080),
+            Some(&work_area),
+            DpiInfo { x: 96, y: 96 },
+        );
+        assert_eq!(left.get_size(), Ok(rect(0, 30, 1920, 1080)));
+        assert_eq!(right.get_size(), Ok(rect(1920, 30, 3840, 1080)));
+
+        let no_work_area =
+            X11Monitor::new(1, &rect(0, 0, 1920, 1080), None, DpiInfo { x: 96, y: 96 });
+        assert_eq!(no_work_area.get_size(), Ok(rect(0, 0, 1920, 1080)));
+    }
+
+    #[test]
+    fn calculates_dpi_from_physical_size() {
+        assert_eq!(calculate_dpi(1920, 508), 96);
+        assert_eq!(calculate_dpi(3840, 597), 163);
+        assert_eq!(calculate_dpi(1920, 0), DEFAULT_DPI);
+    }
+}
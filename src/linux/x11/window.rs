@@ -0,0 +1,302 @@
+use std::rc::Rc;
+
+use log::info;
+
+use x11rb::errors::ReplyError;
+use x11rb::protocol::xproto::{AtomEnum, ConnectionExt as _, Window as XWindow};
+
+use crate::common::enums::WindowState;
+use crate::common::monitor_topology::find_nearest_monitor;
+use crate::common::structs::{Rect, WindowBorderSize};
+use crate::common::traits::{Monitor, Window};
+use crate::{Error, Result};
+
+use super::connection::X11Connection;
+
+// _NET_WM_STATE actions
+const NET_WM_STATE_REMOVE: u32 = 0;
+const NET_WM_STATE_ADD: u32 = 1;
+// Source indication of EWMH requests: sent by a pager, i.e. on behalf of the user
+const SOURCE_PAGER: u32 = 2;
+// ICCCM state requested with WM_CHANGE_STATE to minimize a window
+const ICONIC_STATE: u32 = 3;
+// Window gravity of _NET_MOVERESIZE_WINDOW where x and y are the position of the client window
+// itself, not of its frame
+const STATIC_GRAVITY: u32 = 10;
+
+pub struct X11Window {
+    pub(crate) connection: Rc<X11Connection>,
+    pub platform_specific_handle: XWindow,
+}
+
+impl X11Window {
+    // _NET_FRAME_EXTENTS (left, right, top, bottom), all zero for windows without a frame or with
+    // a window manager that doesn't set the extents
+    fn get_frame_extents(&self) -> Result<[u32; 4]> {
+        let extents = self.connection.get_property32(
+            self.platform_specific_handle,
+            self.connection.atoms._NET_FRAME_EXTENTS,
+            AtomEnum::CARDINAL,
+        )?;
+        Ok(match extents[..] {
+            [left, right, top, bottom] => [left, right, top, bottom],
+            _ => [0; 4],
+        })
+    }
+
+    fn get_wm_state(&self) -> Result<Vec<u32>> {
+        self.connection.get_property32(
+            self.platform_specific_handle,
+            self.connection.atoms._NET_WM_STATE,
+            AtomEnum::ATOM,
+        )
+    }
+
+    fn change_wm_state(&self, action: u32) -> Result<()> {
+        let atoms = &self.connection.atoms;
+        self.connection.send_client_message(
+            self.platform_specific_handle,
+            atoms._NET_WM_STATE,
+            [
+                action,
+                atoms._NET_WM_STATE_MAXIMIZED_VERT,
+                atoms._NET_WM_STATE_MAXIMIZED_HORZ,
+                SOURCE_PAGER,
+                0,
+            ],
+        )
+    }
+}
+
+impl Window for X11Window {
+    fn move_window(&self, windows_rect: &Rect) -> Result<()> {
+        info!(
+            "move_window: position: left={}, top={}, width={}, height={}",
+            windows_rect.left,
+            windows_rect.top,
+            windows_rect.width(),
+            windows_rect.height()
+        );
+        let client_rect = client_rect_from_window_rect(windows_rect, self.get_frame_extents()?);
+        // Gravity, then flags for x, y, width and height being set, then the source
+        let flags = STATIC_GRAVITY | 0xf << 8 | SOURCE_PAGER << 12;
+        self.connection.send_client_message(
+            self.platform_specific_handle,
+            self.connection.atoms._NET_MOVERESIZE_WINDOW,
+            [
+                flags,
+                client_rect.left as u32,
+                client_rect.top as u32,
+                client_rect.width().max(1) as u32,
+                client_rect.height().max(1) as u32,
+            ],
+        )
+    }
+
+    fn get_position(&self) -> Result<Rect> {
+        let conn = &self.connection.conn;
+        let window = self.platform_specific_handle;
+        let geometry = conn
+            .get_geometry(window)
+            .map_err(ReplyError::from)
+            .and_then(|cookie| cookie.reply())
+            .map_err(|error| Error::os("GetGeometry", error))?;
+        let origin = conn
+            .translate_coordinates(window, self.connection.root, 0, 0)
+            .map_err(ReplyError::from)
+            .and_then(|cookie| cookie.reply())
+            .map_err(|error| Error::os("TranslateCoordinates", error))?;
+        let client_rect = Rect {
+            left: origin.dst_x.into(),
+            top: origin.dst_y.into(),
+            right: i32::from(origin.dst_x) + i32::from(geometry.width),
+            bottom: i32::from(origin.dst_y) + i32::from(geometry.height),
+        };
+        let rect = window_rect_from_client_rect(&client_rect, self.get_frame_extents()?);
+        info!("get_position: {:?}", rect);
+        Ok(rect)
+    }
+
+    fn get_state(&self) -> Result<WindowState> {
+        let atoms = &self.connection.atoms;
+        let wm_state = self.get_wm_state()?;
+        let state = if wm_state.contains(&atoms._NET_WM_STATE_HIDDEN) {
+            WindowState::Minimized
+        } else if wm_state.contains(&atoms._NET_WM_STATE_MAXIMIZED_VERT)
+            && wm_state.contains(&atoms._NET_WM_STATE_MAXIMIZED_HORZ)
+        {
+            WindowState::Maximized
+        } else {
+            WindowState::Normal
+        };
+        info!("get_state: state: {:?}", state);
+        Ok(state)
+    }
+
+    fn restore(&self) -> Result<()> {
+        info!("restore.");
+        if self.get_state()? == WindowState::Minimized {
+            // Activating a minimized window maps it again
+            self.connection.send_client_message(
+                self.platform_specific_handle,
+                self.connection.atoms._NET_ACTIVE_WINDOW,
+                [SOURCE_PAGER, 0, 0, 0, 0],
+            )?;
+        }
+        self.change_wm_state(NET_WM_STATE_REMOVE)
+    }
+
+    fn minimize(&self) -> Result<()> {
+        info!("minimize");
+        self.connection.send_client_message(
+            self.platform_specific_handle,
+            self.connection.atoms.WM_CHANGE_STATE,
+            [ICONIC_STATE, 0, 0, 0, 0],
+        )
+    }
+
+    fn maximize(&self) -> Result<()> {
+        info!("maximize");
+        self.change_wm_state(NET_WM_STATE_ADD)
+    }
+
+    fn disable_snapping(&self) -> Result<()> {
+        info!("disable_snapping");
+        // A maximized window can't be moved, the closest thing X11 has to a snapped window
+        if self.get_state()? == WindowState::Maximized {
+            self.change_wm_state(NET_WM_STATE_REMOVE)?;
+        }
+        Ok(())
+    }
+
+    fn get_margin(&self) -> Result<WindowBorderSize> {
+        let margin = margin_from_frame_extents(self.get_frame_extents()?);
+        info!("get_margin: margin: {:?}", margin);
+        Ok(margin)
+    }
+
+    fn get_current_monitor(&self) -> Result<Box<dyn Monitor>> {
+        let mut monitors = self.connection.get_monitors()?;
+        let bounds = monitors
+            .iter()
+            .map(|monitor| monitor.get_size())
+            .collect::<Result<Vec<Rect>>>()?;
+        let index =
+            find_nearest_monitor(&bounds, &self.get_position()?).ok_or(Error::NoMonitors)?;
+        let monitor = monitors.swap_remove(index);
+        info!(
+            "get_current_monitor: handle: {:?}",
+            monitor.platform_specific_handle
+        );
+        Ok(Box::new(monitor))
+    }
+
+    fn get_platform_specific_handle(&self) -> isize {
+        self.platform_specific_handle as isize
+    }
+}
+
+/// Margin between the window rect and its frame, given `_NET_FRAME_EXTENTS` (left, right, top,
+/// bottom). The frame is the visible part of the window, so the margins are the opposite of the
+/// invisible borders on Windows. The window rect already includes the title bar, see
+/// `window_rect_from_client_rect`, so there's no top margin.
+fn margin_from_frame_extents(extents: [u32; 4]) -> WindowBorderSize {
+    let [left, right, _, bottom] = extents.map(|extent| extent as i32);
+    WindowBorderSize {
+        left,
+        right: -right,
+        top: 0,
+        bottom: -bottom,
+    }
+}
+
+/// Window rect of a client window, extended upwards by the top frame extent. Windows are placed
+/// with the top of their rect at the top of the zone, like the title bar on Windows, so the title
+/// bar above the client window stays inside the zone.
+fn window_rect_from_client_rect(client_rect: &Rect, extents: [u32; 4]) -> Rect {
+    Rect {
+        top: client_rect.top - extents[2] as i32,
+        ..client_rect.clone()
+    }
+}
+
+/// Inverse of `window_rect_from_client_rect`
+fn client_rect_from_window_rect(window_rect: &Rect, extents: [u32; 4]) -> Rect {
+    Rect {
+        top: window_rect.top + extents[2] as i32,
+        ..window_rect.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::calculation::calculate_resized_window_rect::{
+        get_visible_rect, get_window_rect_from_visible,
+    };
+    use crate::common::calculation::calculate_window_rect::calculate_window_rect;
+    use crate::common::hotkey_action::HotKeyAction::{MoveWindowToLeftTop, MoveWindowToTop};
+    use crate::common::structs::Gaps;
+
+    // Frame extents of 2, 3, 24 and 4 pixels, the title bar is above the client window
+    const EXTENTS: [u32; 4] = [2, 3, 24, 4];
+
+    #[test]
+    fn frame_is_the_visible_rect() {
+        let margin = margin_from_frame_extents(EXTENTS);
+        let client = Rect {
+            left: 100,
+            top: 100,
+            right: 500,
+            bottom: 400,
+        };
+        let window = window_rect_from_client_rect(&client, EXTENTS);
+        let frame = Rect {
+            left: 98,
+            top: 76,
+            right: 503,
+            bottom: 404,
+        };
+        assert_eq!(get_visible_rect(&window, &margin), frame);
+        assert_eq!(get_window_rect_from_visible(&frame, &margin), window);
+        assert_eq!(client_rect_from_window_rect(&window, EXTENTS), client);
+    }
+
+    #[test]
+    fn frame_with_title_bar_fills_the_zone() {
+        let margin = margin_from_frame_extents(EXTENTS);
+        let monitor = Rect {
+            left: 0,
+            right: 1920,
+            top: 30,
+            bottom: 1080,
+        };
+        let window = calculate_window_rect(&monitor, &margin, &Gaps::default(), MoveWindowToTop);
+        assert_eq!(
+            get_visible_rect(&window, &margin),
+            Rect {
+                left: 0,
+                right: 1920,
+                top: 30,
+                bottom: 555,
+            }
+        );
+        // The client window is placed below the title bar
+        assert_eq!(client_rect_from_window_rect(&window, EXTENTS).top, 54);
+
+        let gaps = Gaps {
+            inner_gap: 10,
+            outer_padding: 20,
+        };
+        let window = calculate_window_rect(&monitor, &margin, &gaps, MoveWindowToLeftTop);
+        assert_eq!(
+            get_visible_rect(&window, &margin),
+            Rect {
+                left: 20,
+                right: 955,
+                top: 50,
+                bottom: 550,
+            }
+        );
+    }
+}
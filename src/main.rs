@@ -1,17 +1,149 @@
-use win_move::common::config::get_config_hotkeys;
-use win_move::common::logic::main_loop;
-use win_move::common::traits::HotkeyHandler;
-use win_move::windows::desktop::WindowsDesktop;
-use win_move::windows::hotkey_handler::WindowsHotKeyHandler;
+use std::fs;
+use std::path::Path;
+use win_move::common::action_registry::format_action_list;
+use win_move::common::cli::{format_monitor_list, parse_command_line, Command, USAGE};
+use win_move::common::config::load_config;
+use win_move::common::config_path::{find_config_path, ConfigPath};
+use win_move::common::config_validation::validate_config;
+use win_move::common::config_watcher::watch_config_file;
+use win_move::common::hotkey::{format_key_list, log_hotkey_registrations};
+use win_move::common::logic::{main_loop, run_single_action};
+use win_move::common::traits::{Desktop, HotkeyHandler};
+#[cfg(target_os = "linux")]
+use win_move::linux::x11::{desktop::X11Desktop, hotkey_handler::X11HotkeyHandler};
+#[cfg(windows)]
+use win_move::windows::{desktop::WindowsDesktop, hotkey_handler::WindowsHotKeyHandler};
+
+#[cfg(not(any(windows, target_os = "linux")))]
+compile_error!("win-move only has backends for Windows and Linux (X11)");
+
+// Log target of the chosen config file, shown by default
+const CONFIG_LOG_TARGET: &str = "win_move::config_path";
 
 fn main() {
-    env_logger::init();
+    // Warnings and the config file the daemon was started with, unless RUST_LOG says otherwise
+    env_logger::Builder::from_env(
+        env_logger::Env::default().default_filter_or(format!("warn,{}=info", CONFIG_LOG_TARGET)),
+    )
+    .init();
+
+    let command_line = match parse_command_line(std::env::args().skip(1).collect()) {
+        Ok(command_line) => command_line,
+        Err(error) => {
+            eprintln!("{}\n\n{}", error, USAGE);
+            std::process::exit(2);
+        }
+    };
+    let config_path = find_config_path(command_line.config_path);
+
+    match command_line.command {
+        Command::Run => run(&config_path),
+        Command::Exec(action) => {
+            let config = load_config(&config_path.path);
+            run_single_action(create_desktop().as_ref(), &config, action)
+                .unwrap_or_else(|error| exit_with_error(error));
+        }
+        Command::ListActions => print!("{}", format_action_list()),
+        Command::ListKeys => print!("{}", format_key_list()),
+        Command::ListMonitors => {
+            let config = load_config(&config_path.path);
+            let list = create_desktop()
+                .get_ordered_monitors(config.monitor_order)
+                .and_then(|monitors| format_monitor_list(&monitors, &config))
+                .unwrap_or_else(|error| exit_with_error(error));
+            print!("{}", list);
+        }
+        Command::CheckConfig(path) => {
+            std::process::exit(check_config(&path.unwrap_or(config_path.path)))
+        }
+        Command::ConfigPath => println!("{}", config_path.path.display()),
+        Command::Help => print!("{}", USAGE),
+    }
+}
+
+// Registers the hotkeys and handles them until Ctrl+C or SIGTERM
+fn run(config_path: &ConfigPath) {
+    log::info!(
+        target: CONFIG_LOG_TARGET,
+        "Config file {:?}: {}",
+        config_path.path,
+        config_path.source
+    );
+    let config = load_config(&config_path.path);
+
+    let hotkey_handler = create_hotkey_handler();
+    let system = create_desktop();
+
+    let registrations = hotkey_handler.register_hotkeys(config.hotkeys.clone());
+    let failed = log_hotkey_registrations(&registrations);
+    if failed > 0 && config.strict_hotkeys {
+        log::error!(
+            "Not starting as 'strict_hotkeys' is set and some hotkeys couldn't be registered"
+        );
+        hotkey_handler.unregister_hotkeys();
+        std::process::exit(1);
+    }
+
+    watch_config_file(
+        config_path.path.clone(),
+        hotkey_handler.get_config_change_notifier(),
+    );
+    // The main loop unregisters the hotkeys before the process exits
+    let quit = hotkey_handler.get_quit_notifier();
+    if let Err(error) = ctrlc::set_handler(quit) {
+        log::warn!("Couldn't install the Ctrl+C handler: {}", error);
+    }
+    main_loop(
+        hotkey_handler.as_ref(),
+        system.as_ref(),
+        config,
+        &config_path.path,
+    );
+}
 
-    let keys = get_config_hotkeys();
+#[cfg(windows)]
+fn create_desktop() -> Box<dyn Desktop> {
+    Box::new(WindowsDesktop::new())
+}
+
+#[cfg(windows)]
+fn create_hotkey_handler() -> Box<dyn HotkeyHandler> {
+    Box::new(WindowsHotKeyHandler::new())
+}
+
+#[cfg(target_os = "linux")]
+fn create_desktop() -> Box<dyn Desktop> {
+    Box::new(X11Desktop::new().unwrap_or_else(|error| exit_with_error(error)))
+}
+
+#[cfg(target_os = "linux")]
+fn create_hotkey_handler() -> Box<dyn HotkeyHandler> {
+    Box::new(X11HotkeyHandler::new().unwrap_or_else(|error| exit_with_error(error)))
+}
+
+fn exit_with_error(error: win_move::Error) -> ! {
+    eprintln!("{}", error);
+    std::process::exit(1);
+}
 
-    let hotkey_handler = WindowsHotKeyHandler::new();
-    let system = WindowsDesktop::new();
+// Validates the configuration file at `path` and prints the problems found. Returns the exit code.
+fn check_config(path: &Path) -> i32 {
+    let contents = match fs::read_to_string(path) {
+        Ok(contents) => contents,
+        Err(error) => {
+            eprintln!("{}: {}", path.display(), error);
+            return 2;
+        }
+    };
 
-    hotkey_handler.register_hotkeys(keys);
-    main_loop(&hotkey_handler, &system);
+    let validated = validate_config(&contents);
+    for diagnostic in &validated.diagnostics {
+        eprintln!("{}", diagnostic.format_with_path(path));
+    }
+    if validated.is_valid() {
+        println!("{}: OK", path.display());
+        0
+    } else {
+        1
+    }
 }
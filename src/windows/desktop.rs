@@ -1,14 +1,15 @@
 use core::ptr;
 
 use windows::Win32::Foundation::BOOL;
-use windows::Win32::Foundation::{LPARAM, RECT};
+use windows::Win32::Foundation::{HWND, LPARAM, RECT};
 use windows::Win32::Graphics::Gdi::EnumDisplayMonitors;
 use windows::Win32::Graphics::Gdi::HDC;
 use windows::Win32::Graphics::Gdi::HMONITOR;
-use windows::Win32::UI::WindowsAndMessaging::GetForegroundWindow;
+use windows::Win32::UI::WindowsAndMessaging::{EnumWindows, GetForegroundWindow, IsWindowVisible};
 
 use crate::common::traits::{Desktop, Monitor, Window};
 use crate::windows::window::WindowsWindow;
+use crate::{Error, Result};
 
 use super::monitor::WindowsMonitor;
 
@@ -20,22 +21,33 @@ impl WindowsDesktop {
     }
 }
 
+impl Default for WindowsDesktop {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl Desktop for WindowsDesktop {
-    fn get_foreground_window(&self) -> Box<dyn Window> {
+    fn get_foreground_window(&self) -> Result<Box<dyn Window>> {
         let foreground_window;
         unsafe {
             foreground_window = GetForegroundWindow();
         }
-        Box::new(WindowsWindow {
+        // No window has the focus eg. while the focus is being switched between windows
+        if foreground_window.0 == 0 {
+            return Err(Error::NoForegroundWindow);
+        }
+        Ok(Box::new(WindowsWindow {
             platform_specific_handle: foreground_window.0,
-        })
+        }))
     }
 
-    fn get_all_monitors(&self) -> Vec<Box<dyn Monitor>> {
+    fn get_all_monitors(&self) -> Result<Vec<Box<dyn Monitor>>> {
         let monitors: Box<Vec<HMONITOR>>;
+        let result;
         unsafe {
             let monitors_pointer = Box::into_raw(Box::new(Vec::<HMONITOR>::new()));
-            let _res_bool = EnumDisplayMonitors(
+            result = EnumDisplayMonitors(
                 HDC(0),
                 ptr::null_mut(),
                 Some(monitor_enum_fn),
@@ -43,12 +55,39 @@ impl Desktop for WindowsDesktop {
             );
             monitors = Box::from_raw(monitors_pointer);
         }
+        result
+            .ok()
+            .map_err(|error| Error::os("EnumDisplayMonitors", error))?;
+        if monitors.is_empty() {
+            return Err(Error::NoMonitors);
+        }
 
         let mut windows_monitors: Vec<Box<dyn Monitor>> = Vec::new();
         for m in *monitors {
             windows_monitors.push(Box::new(WindowsMonitor::new(m)));
         }
-        windows_monitors
+        Ok(windows_monitors)
+    }
+
+    fn get_all_windows(&self) -> Result<Vec<Box<dyn Window>>> {
+        let windows: Box<Vec<HWND>>;
+        let result;
+        unsafe {
+            let windows_pointer = Box::into_raw(Box::new(Vec::<HWND>::new()));
+            result = EnumWindows(Some(window_enum_fn), LPARAM(windows_pointer as isize));
+            windows = Box::from_raw(windows_pointer);
+        }
+        result
+            .ok()
+            .map_err(|error| Error::os("EnumWindows", error))?;
+
+        let mut windows_windows: Vec<Box<dyn Window>> = Vec::new();
+        for w in *windows {
+            windows_windows.push(Box::new(WindowsWindow {
+                platform_specific_handle: w.0,
+            }));
+        }
+        Ok(windows_windows)
     }
 }
 
@@ -62,3 +101,11 @@ unsafe extern "system" fn monitor_enum_fn(
     _param3.push(param0);
     BOOL::from(true)
 }
+
+unsafe extern "system" fn window_enum_fn(param0: HWND, param1: LPARAM) -> BOOL {
+    if IsWindowVisible(param0).as_bool() {
+        let param1 = Box::leak(Box::from_raw(param1.0 as *mut Vec<HWND>));
+        param1.push(param0);
+    }
+    BOOL::from(true)
+}
@@ -1,40 +1,117 @@
-use num::FromPrimitive;
+use std::cell::RefCell;
+use std::collections::HashMap;
+
 use windows::Win32::Foundation::{HWND, LPARAM, POINT, WPARAM};
+use windows::Win32::System::Threading::GetCurrentThreadId;
 use windows::Win32::UI::Input::KeyboardAndMouse;
-use windows::Win32::UI::Input::KeyboardAndMouse::{RegisterHotKey, HOT_KEY_MODIFIERS, VIRTUAL_KEY};
-use windows::Win32::UI::WindowsAndMessaging::{GetMessageW, MSG};
+use windows::Win32::UI::Input::KeyboardAndMouse::{
+    RegisterHotKey, UnregisterHotKey, HOT_KEY_MODIFIERS, VIRTUAL_KEY,
+};
+use windows::Win32::UI::WindowsAndMessaging::{
+    GetMessageW, PostThreadMessageW, MSG, WM_APP, WM_HOTKEY, WM_QUIT,
+};
+
+use crate::common::{
+    enums::HandlerEvent,
+    hotkey::{find_duplicate_hotkeys, HotkeyRegistration, HotkeyRegistrationStatus},
+    structs::HotkeyMapping,
+    traits::HotkeyHandler,
+};
 
-use crate::common::{hotkey_action::HotKeyAction, structs::HotkeyMapping, traits::HotkeyHandler};
+// Hotkey ids are assigned sequentially starting from here, in the order the hotkeys are configured
+const FIRST_HOTKEY_ID: i32 = 1;
 
-pub struct WindowsHotKeyHandler {}
+// Thread message posted when the configuration file changes
+const WM_CONFIG_CHANGED: u32 = WM_APP + 1;
+
+pub struct WindowsHotKeyHandler {
+    registered_hotkeys: RefCell<HashMap<i32, HotkeyMapping>>,
+    // Thread whose message queue receives the hotkey messages, the one that created the handler
+    thread_id: u32,
+}
 
 impl WindowsHotKeyHandler {
     pub fn new() -> Self {
-        Self {}
+        Self {
+            registered_hotkeys: RefCell::new(HashMap::new()),
+            thread_id: unsafe { GetCurrentThreadId() },
+        }
     }
 
-    fn do_register_hotkeys(&self, hot_keys: Vec<HotkeyMappingWin>) {
-        for hot_key in hot_keys.iter() {
-            let VIRTUAL_KEY(key_usize) = hot_key.key;
-            unsafe {
-                RegisterHotKey(
-                    HWND(0),
-                    hot_key.action as i32,
-                    hot_key.modifier,
-                    key_usize.into(),
+    fn do_register_hotkeys(&self, hot_keys: Vec<HotkeyMappingWin>) -> Vec<HotkeyRegistration> {
+        let mut registered_hotkeys = self.registered_hotkeys.borrow_mut();
+        let first_id = registered_hotkeys
+            .keys()
+            .max()
+            .map_or(FIRST_HOTKEY_ID, |id| id + 1);
+        let mappings: Vec<HotkeyMapping> = hot_keys.iter().map(|h| h.mapping.clone()).collect();
+        let duplicates = find_duplicate_hotkeys(&mappings);
+
+        (first_id..)
+            .zip(hot_keys)
+            .zip(duplicates)
+            .map(|((id, hot_key), duplicate)| {
+                let status = match duplicate {
+                    Some(previous) => HotkeyRegistrationStatus::Duplicate { previous },
+                    None => {
+                        let VIRTUAL_KEY(key_usize) = hot_key.key;
+                        let registered = unsafe {
+                            RegisterHotKey(HWND(0), id, hot_key.modifier, key_usize.into())
+                        };
+                        if registered.as_bool() {
+                            registered_hotkeys.insert(id, hot_key.mapping.clone());
+                            HotkeyRegistrationStatus::Registered
+                        } else {
+                            HotkeyRegistrationStatus::Failed(
+                                windows::core::Error::from_win32().message().to_string(),
+                            )
+                        }
+                    }
+                };
+                HotkeyRegistration {
+                    mapping: hot_key.mapping,
+                    status,
+                }
+            })
+            .collect()
+    }
+
+    fn get_message_sender(&self, message: u32) -> Box<dyn Fn() + Send> {
+        let thread_id = self.thread_id;
+        Box::new(move || {
+            let posted = unsafe { PostThreadMessageW(thread_id, message, WPARAM(0), LPARAM(0)) };
+            if !posted.as_bool() {
+                log::warn!(
+                    "Failed to send a message to the main loop: {}",
+                    windows::core::Error::from_win32().message()
                 );
             }
-        }
+        })
+    }
+}
+
+impl Default for WindowsHotKeyHandler {
+    fn default() -> Self {
+        Self::new()
     }
 }
 
 impl HotkeyHandler for WindowsHotKeyHandler {
-    fn register_hotkeys(&self, keys: Vec<HotkeyMapping>) {
+    fn register_hotkeys(&self, keys: Vec<HotkeyMapping>) -> Vec<HotkeyRegistration> {
         let hot_keys = map_keys_from_config(keys);
-        self.do_register_hotkeys(hot_keys);
+        self.do_register_hotkeys(hot_keys)
     }
 
-    fn get_action_from_pressed_key(&self) -> HotKeyAction {
+    fn unregister_hotkeys(&self) {
+        for (id, mapping) in self.registered_hotkeys.borrow_mut().drain() {
+            let unregistered = unsafe { UnregisterHotKey(HWND(0), id) };
+            if !unregistered.as_bool() {
+                log::warn!("Failed to unregister hotkey {}", mapping.hotkey);
+            }
+        }
+    }
+
+    fn get_next_event(&self) -> HandlerEvent {
         let mut message = MSG {
             hwnd: HWND(0),
             message: 0,
@@ -44,36 +121,104 @@ impl HotkeyHandler for WindowsHotKeyHandler {
             pt: POINT { x: 0, y: 0 },
         };
 
-        unsafe {
-            let _message_return = GetMessageW(&mut message, HWND(0), 0, 0);
+        // 0 is returned for WM_QUIT and -1 if the message couldn't be read
+        let message_return = unsafe { GetMessageW(&mut message, HWND(0), 0, 0) };
+        match message_return.0 {
+            0 => return HandlerEvent::Quit,
+            -1 => {
+                log::error!(
+                    "Couldn't read the message queue: {}",
+                    windows::core::Error::from_win32().message()
+                );
+                return HandlerEvent::Quit;
+            }
+            _ => (),
         }
 
-        let WPARAM(pressed_key_usize) = message.wParam;
-        let parsed_key = u32::try_from(pressed_key_usize).unwrap();
-        HotKeyAction::from_u32(parsed_key).unwrap()
+        match message.message {
+            WM_CONFIG_CHANGED => HandlerEvent::ConfigChanged,
+            WM_HOTKEY => {
+                let WPARAM(hotkey_id) = message.wParam;
+                let mapping = i32::try_from(hotkey_id)
+                    .ok()
+                    .and_then(|id| self.registered_hotkeys.borrow().get(&id).cloned());
+                match mapping {
+                    Some(mapping) => HandlerEvent::Hotkey(mapping.action),
+                    None => {
+                        log::warn!("Received unknown hotkey id {}", hotkey_id);
+                        HandlerEvent::Ignored
+                    }
+                }
+            }
+            _ => HandlerEvent::Ignored,
+        }
+    }
+
+    fn get_config_change_notifier(&self) -> Box<dyn Fn() + Send> {
+        self.get_message_sender(WM_CONFIG_CHANGED)
+    }
+
+    fn get_quit_notifier(&self) -> Box<dyn Fn() + Send> {
+        self.get_message_sender(WM_QUIT)
     }
 }
 
 struct HotkeyMappingWin {
-    action: HotKeyAction,
+    mapping: HotkeyMapping,
     key: VIRTUAL_KEY,
     modifier: HOT_KEY_MODIFIERS,
 }
 
-use crate::common::enums::{HotKeyButton, HotKeyModifier};
+use crate::common::{enums::HotKeyButton, hotkey::HotKeyModifiers};
 
 fn map_keys_from_config(keys: Vec<HotkeyMapping>) -> Vec<HotkeyMappingWin> {
     keys.into_iter()
         .map(|mapping| HotkeyMappingWin {
-            action: mapping.action,
-            key: map_button_to_virtual_key(&mapping.key),
-            modifier: map_modifier_to_hotkey_modifier(&mapping.modifier),
+            key: map_button_to_virtual_key(&mapping.hotkey.key),
+            modifier: map_modifiers_to_hotkey_modifiers(&mapping.hotkey.modifiers),
+            mapping,
         })
         .collect()
 }
 
 fn map_button_to_virtual_key(button: &HotKeyButton) -> VIRTUAL_KEY {
     match button {
+        HotKeyButton::VkA => KeyboardAndMouse::VK_A,
+        HotKeyButton::VkB => KeyboardAndMouse::VK_B,
+        HotKeyButton::VkC => KeyboardAndMouse::VK_C,
+        HotKeyButton::VkD => KeyboardAndMouse::VK_D,
+        HotKeyButton::VkE => KeyboardAndMouse::VK_E,
+        HotKeyButton::VkF => KeyboardAndMouse::VK_F,
+        HotKeyButton::VkG => KeyboardAndMouse::VK_G,
+        HotKeyButton::VkH => KeyboardAndMouse::VK_H,
+        HotKeyButton::VkI => KeyboardAndMouse::VK_I,
+        HotKeyButton::VkJ => KeyboardAndMouse::VK_J,
+        HotKeyButton::VkK => KeyboardAndMouse::VK_K,
+        HotKeyButton::VkL => KeyboardAndMouse::VK_L,
+        HotKeyButton::VkM => KeyboardAndMouse::VK_M,
+        HotKeyButton::VkN => KeyboardAndMouse::VK_N,
+        HotKeyButton::VkO => KeyboardAndMouse::VK_O,
+        HotKeyButton::VkP => KeyboardAndMouse::VK_P,
+        HotKeyButton::VkQ => KeyboardAndMouse::VK_Q,
+        HotKeyButton::VkR => KeyboardAndMouse::VK_R,
+        HotKeyButton::VkS => KeyboardAndMouse::VK_S,
+        HotKeyButton::VkT => KeyboardAndMouse::VK_T,
+        HotKeyButton::VkU => KeyboardAndMouse::VK_U,
+        HotKeyButton::VkV => KeyboardAndMouse::VK_V,
+        HotKeyButton::VkW => KeyboardAndMouse::VK_W,
+        HotKeyButton::VkX => KeyboardAndMouse::VK_X,
+        HotKeyButton::VkY => KeyboardAndMouse::VK_Y,
+        HotKeyButton::VkZ => KeyboardAndMouse::VK_Z,
+        HotKeyButton::Vk0 => KeyboardAndMouse::VK_0,
+        HotKeyButton::Vk1 => KeyboardAndMouse::VK_1,
+        HotKeyButton::Vk2 => KeyboardAndMouse::VK_2,
+        HotKeyButton::Vk3 => KeyboardAndMouse::VK_3,
+        HotKeyButton::Vk4 => KeyboardAndMouse::VK_4,
+        HotKeyButton::Vk5 => KeyboardAndMouse::VK_5,
+        HotKeyButton::Vk6 => KeyboardAndMouse::VK_6,
+        HotKeyButton::Vk7 => KeyboardAndMouse::VK_7,
+        HotKeyButton::Vk8 => KeyboardAndMouse::VK_8,
+        HotKeyButton::Vk9 => KeyboardAndMouse::VK_9,
         HotKeyButton::VkNumpad0 => KeyboardAndMouse::VK_NUMPAD0,
         HotKeyButton::VkNumpad1 => KeyboardAndMouse::VK_NUMPAD1,
         HotKeyButton::VkNumpad2 => KeyboardAndMouse::VK_NUMPAD2,
@@ -85,13 +230,74 @@ fn map_button_to_virtual_key(button: &HotKeyButton) -> VIRTUAL_KEY {
         HotKeyButton::VkNumpad8 => KeyboardAndMouse::VK_NUMPAD8,
         HotKeyButton::VkNumpad9 => KeyboardAndMouse::VK_NUMPAD9,
         HotKeyButton::VkDecimal => KeyboardAndMouse::VK_DECIMAL,
+        HotKeyButton::VkMultiply => KeyboardAndMouse::VK_MULTIPLY,
+        HotKeyButton::VkAdd => KeyboardAndMouse::VK_ADD,
+        HotKeyButton::VkSubtract => KeyboardAndMouse::VK_SUBTRACT,
+        HotKeyButton::VkDivide => KeyboardAndMouse::VK_DIVIDE,
+        HotKeyButton::VkF1 => KeyboardAndMouse::VK_F1,
+        HotKeyButton::VkF2 => KeyboardAndMouse::VK_F2,
+        HotKeyButton::VkF3 => KeyboardAndMouse::VK_F3,
+        HotKeyButton::VkF4 => KeyboardAndMouse::VK_F4,
+        HotKeyButton::VkF5 => KeyboardAndMouse::VK_F5,
+        HotKeyButton::VkF6 => KeyboardAndMouse::VK_F6,
+        HotKeyButton::VkF7 => KeyboardAndMouse::VK_F7,
+        HotKeyButton::VkF8 => KeyboardAndMouse::VK_F8,
+        HotKeyButton::VkF9 => KeyboardAndMouse::VK_F9,
+        HotKeyButton::VkF10 => KeyboardAndMouse::VK_F10,
+        HotKeyButton::VkF11 => KeyboardAndMouse::VK_F11,
+        HotKeyButton::VkF12 => KeyboardAndMouse::VK_F12,
+        HotKeyButton::VkF13 => KeyboardAndMouse::VK_F13,
+        HotKeyButton::VkF14 => KeyboardAndMouse::VK_F14,
+        HotKeyButton::VkF15 => KeyboardAndMouse::VK_F15,
+        HotKeyButton::VkF16 => KeyboardAndMouse::VK_F16,
+        HotKeyButton::VkF17 => KeyboardAndMouse::VK_F17,
+        HotKeyButton::VkF18 => KeyboardAndMouse::VK_F18,
+        HotKeyButton::VkF19 => KeyboardAndMouse::VK_F19,
+        HotKeyButton::VkF20 => KeyboardAndMouse::VK_F20,
+        HotKeyButton::VkF21 => KeyboardAndMouse::VK_F21,
+        HotKeyButton::VkF22 => KeyboardAndMouse::VK_F22,
+        HotKeyButton::VkF23 => KeyboardAndMouse::VK_F23,
+        HotKeyButton::VkF24 => KeyboardAndMouse::VK_F24,
+        HotKeyButton::VkLeft => KeyboardAndMouse::VK_LEFT,
+        HotKeyButton::VkRight => KeyboardAndMouse::VK_RIGHT,
+        HotKeyButton::VkUp => KeyboardAndMouse::VK_UP,
+        HotKeyButton::VkDown => KeyboardAndMouse::VK_DOWN,
+        HotKeyButton::VkHome => KeyboardAndMouse::VK_HOME,
+        HotKeyButton::VkEnd => KeyboardAndMouse::VK_END,
+        HotKeyButton::VkPageUp => KeyboardAndMouse::VK_PRIOR,
+        HotKeyButton::VkPageDown => KeyboardAndMouse::VK_NEXT,
+        HotKeyButton::VkInsert => KeyboardAndMouse::VK_INSERT,
+        HotKeyButton::VkDelete => KeyboardAndMouse::VK_DELETE,
+        HotKeyButton::VkBackspace => KeyboardAndMouse::VK_BACK,
+        HotKeyButton::VkTab => KeyboardAndMouse::VK_TAB,
+        HotKeyButton::VkEnter => KeyboardAndMouse::VK_RETURN,
+        HotKeyButton::VkEscape => KeyboardAndMouse::VK_ESCAPE,
+        HotKeyButton::VkSpace => KeyboardAndMouse::VK_SPACE,
+        HotKeyButton::VkComma => KeyboardAndMouse::VK_OEM_COMMA,
+        HotKeyButton::VkPeriod => KeyboardAndMouse::VK_OEM_PERIOD,
+        HotKeyButton::VkMinus => KeyboardAndMouse::VK_OEM_MINUS,
+        HotKeyButton::VkEquals => KeyboardAndMouse::VK_OEM_PLUS,
+        HotKeyButton::VkSemicolon => KeyboardAndMouse::VK_OEM_1,
+        HotKeyButton::VkSlash => KeyboardAndMouse::VK_OEM_2,
+        HotKeyButton::VkBackquote => KeyboardAndMouse::VK_OEM_3,
+        HotKeyButton::VkLeftBracket => KeyboardAndMouse::VK_OEM_4,
+        HotKeyButton::VkBackslash => KeyboardAndMouse::VK_OEM_5,
+        HotKeyButton::VkRightBracket => KeyboardAndMouse::VK_OEM_6,
+        HotKeyButton::VkQuote => KeyboardAndMouse::VK_OEM_7,
     }
 }
 
-fn map_modifier_to_hotkey_modifier(modifier: &HotKeyModifier) -> HOT_KEY_MODIFIERS {
-    match modifier {
-        HotKeyModifier::None => KeyboardAndMouse::HOT_KEY_MODIFIERS(0),
-        HotKeyModifier::ModControl => KeyboardAndMouse::MOD_CONTROL,
-        HotKeyModifier::ModAlt => KeyboardAndMouse::MOD_ALT,
+fn map_modifiers_to_hotkey_modifiers(modifiers: &HotKeyModifiers) -> HOT_KEY_MODIFIERS {
+    let mut hotkey_modifiers = KeyboardAndMouse::HOT_KEY_MODIFIERS(0);
+    for (held, modifier) in [
+        (modifiers.control, KeyboardAndMouse::MOD_CONTROL),
+        (modifiers.alt, KeyboardAndMouse::MOD_ALT),
+        (modifiers.shift, KeyboardAndMouse::MOD_SHIFT),
+        (modifiers.super_key, KeyboardAndMouse::MOD_WIN),
+    ] {
+        if held {
+            hotkey_modifiers |= modifier;
+        }
     }
+    hotkey_modifiers
 }
@@ -6,6 +6,7 @@ use windows::Win32::UI::HiDpi::{GetDpiForMonitor, MDT_EFFECTIVE_DPI};
 use crate::common::structs::DpiInfo;
 use crate::common::structs::Rect;
 use crate::common::traits::Monitor;
+use crate::{Error, Result};
 
 use super::helpers::get_monitor_info_struct;
 
@@ -20,36 +21,43 @@ impl WindowsMonitor {
         }
     }
 
-    fn get_monitor_info(&self) -> MONITORINFO {
+    fn get_monitor_info(&self) -> Result<MONITORINFO> {
         let mut monitor_info = get_monitor_info_struct();
-        unsafe {
-            GetMonitorInfoW(self.platform_specific_handle, &mut monitor_info);
-        }
-        monitor_info
+        unsafe { GetMonitorInfoW(self.platform_specific_handle, &mut monitor_info) }
+            .ok()
+            .map_err(|error| self.error("GetMonitorInfoW", error))?;
+        Ok(monitor_info)
+    }
+
+    fn error(&self, operation: &'static str, error: windows::core::Error) -> Error {
+        Error::os(
+            operation,
+            format!("monitor {}: {}", self.platform_specific_handle.0, error),
+        )
     }
 }
 
 impl Monitor for WindowsMonitor {
-    fn get_dpi_info(&self) -> crate::common::structs::DpiInfo {
+    fn get_dpi_info(&self) -> Result<crate::common::structs::DpiInfo> {
         let mut dpi_x: Box<u32> = Box::new(0);
         let mut dpi_y: Box<u32> = Box::new(0);
         unsafe {
-            // Ignore error from GetDpiForMonitor
-            if let Ok(_res) = GetDpiForMonitor(
+            GetDpiForMonitor(
                 self.platform_specific_handle,
                 MDT_EFFECTIVE_DPI,
                 &mut *dpi_x,
                 &mut *dpi_y,
-            ) {};
+            )
         }
-        DpiInfo {
+        .map_err(|error| self.error("GetDpiForMonitor", error))?;
+        Ok(DpiInfo {
             x: *dpi_x,
             y: *dpi_y,
-        }
+        })
     }
 
-    fn get_size(&self) -> Rect {
-        Rect::from(&self.get_monitor_info())
+    fn get_size(&self) -> Result<Rect> {
+        Ok(Rect::from(&self.get_monitor_info()?))
     }
 
     fn get_platform_specific_handle(&self) -> isize {
@@ -1,9 +1,7 @@
-extern crate num;
-
 use std::convert::TryFrom;
 use std::mem::size_of;
 
-use log::{error, info}; // Add log macros
+use log::info; // Add log macros
 
 use windows::Win32::Foundation::{HWND, POINT, RECT};
 use windows::Win32::Graphics::Dwm::{DwmGetWindowAttribute, DWMWA_EXTENDED_FRAME_BOUNDS};
@@ -17,6 +15,7 @@ use windows::Win32::UI::WindowsAndMessaging::{
 use crate::common::enums::WindowState;
 use crate::common::structs::{Rect, WindowBorderSize, WindowPosition};
 use crate::common::traits::{Monitor, Window};
+use crate::{Error, Result};
 
 use super::{helpers::get_rect_struct, monitor::WindowsMonitor};
 
@@ -25,23 +24,22 @@ pub struct WindowsWindow {
 }
 
 impl WindowsWindow {
-    fn get_platform_specific_handle(&self) -> HWND {
-        info!(
-            "get_platform_specific_handle: {:?}",
-            self.platform_specific_handle
-        );
+    fn get_hwnd(&self) -> HWND {
+        info!("get_hwnd: {:?}", self.platform_specific_handle);
         HWND(self.platform_specific_handle)
     }
 
-    fn show_window(&self, ncmdshow: SHOW_WINDOW_CMD) {
+    // The return value of ShowWindow is whether the window was visible before, not an error
+    fn show_window(&self, ncmdshow: SHOW_WINDOW_CMD) -> Result<()> {
         info!("show_window: command: {:?}", ncmdshow);
         unsafe {
-            ShowWindow(self.get_platform_specific_handle(), ncmdshow);
+            ShowWindow(self.get_hwnd(), ncmdshow);
         }
+        Ok(())
     }
 
     // https://docs.microsoft.com/en-gb/windows/win32/api/winuser/nf-winuser-getwindowplacement
-    fn get_window_internal_info(&self) -> WINDOWPLACEMENT {
+    fn get_window_internal_info(&self) -> Result<WINDOWPLACEMENT> {
         let mut window_info = WINDOWPLACEMENT {
             length: u32::try_from(size_of::<WINDOWPLACEMENT>()).unwrap(),
             flags: WINDOWPLACEMENT_FLAGS(0),
@@ -51,16 +49,23 @@ impl WindowsWindow {
             rcNormalPosition: get_rect_struct(),
         };
 
-        unsafe {
-            GetWindowPlacement(self.get_platform_specific_handle(), &mut window_info);
-        };
+        unsafe { GetWindowPlacement(self.get_hwnd(), &mut window_info) }
+            .ok()
+            .map_err(|error| self.error("GetWindowPlacement", error))?;
         info!("get_window_internal_info: {:?}", window_info);
-        window_info
+        Ok(window_info)
+    }
+
+    fn error(&self, operation: &'static str, error: windows::core::Error) -> Error {
+        Error::os(
+            operation,
+            format!("window {}: {}", self.platform_specific_handle, error),
+        )
     }
 }
 
 impl Window for WindowsWindow {
-    fn move_window(&self, windows_rect: &crate::common::structs::Rect) {
+    fn move_window(&self, windows_rect: &crate::common::structs::Rect) -> Result<()> {
         info!(
             "move_window: position: left={}, top={}, width={}, height={}",
             windows_rect.left,
@@ -71,21 +76,24 @@ impl Window for WindowsWindow {
         let a = WindowPosition::from(windows_rect);
         unsafe {
             MoveWindow(
-                self.get_platform_specific_handle(),
+                self.get_hwnd(),
                 windows_rect.left,
                 windows_rect.top,
                 a.width,
                 a.height,
                 true,
-            );
+            )
         }
+        .ok()
+        .map_err(|error| self.error("MoveWindow", error))?;
         if log::log_enabled!(log::Level::Info) {
-            self.get_window_internal_info();
+            self.get_window_internal_info()?;
         }
+        Ok(())
     }
 
-    fn get_state(&self) -> crate::common::enums::WindowState {
-        let window_info = self.get_window_internal_info();
+    fn get_state(&self) -> Result<crate::common::enums::WindowState> {
+        let window_info = self.get_window_internal_info()?;
         let state = match window_info.showCmd {
             SW_SHOWNORMAL => WindowState::Normal,
             SW_SHOWMINIMIZED => WindowState::Minimized,
@@ -93,60 +101,57 @@ impl Window for WindowsWindow {
             _ => WindowState::Other,
         };
         info!("get_state: state: {:?}", state);
-        state
+        Ok(state)
     }
 
-    fn restore(&self) {
+    fn restore(&self) -> Result<()> {
         info!("restore.");
         self.show_window(SW_RESTORE)
     }
 
-    fn minimize(&self) {
+    fn minimize(&self) -> Result<()> {
         info!("minimize");
         self.show_window(SW_SHOWMINIMIZED)
     }
 
-    fn maximize(&self) {
+    fn maximize(&self) -> Result<()> {
         info!("maximize");
         self.show_window(SW_SHOWMAXIMIZED)
     }
 
-    fn get_position(&self) -> crate::common::structs::Rect {
+    fn get_position(&self) -> Result<crate::common::structs::Rect> {
         let mut r = get_rect_struct();
-        unsafe {
-            GetWindowRect(self.get_platform_specific_handle(), &mut r);
-        }
+        unsafe { GetWindowRect(self.get_hwnd(), &mut r) }
+            .ok()
+            .map_err(|error| self.error("GetWindowRect", error))?;
         let rect = Rect::from(&r);
         info!("get_position: {:?}", rect);
-        rect
+        Ok(rect)
     }
 
-    fn disable_snapping(&self) {
+    fn disable_snapping(&self) -> Result<()> {
         info!("disable_snapping");
-        let mut window_info = self.get_window_internal_info();
+        let mut window_info = self.get_window_internal_info()?;
         window_info.showCmd = SW_SHOWNORMAL;
-        unsafe {
-            SetWindowPlacement(self.get_platform_specific_handle(), &window_info);
-        }
+        unsafe { SetWindowPlacement(self.get_hwnd(), &window_info) }
+            .ok()
+            .map_err(|error| self.error("SetWindowPlacement", error))
     }
 
-    fn get_margin(&self) -> crate::common::structs::WindowBorderSize {
+    fn get_margin(&self) -> Result<crate::common::structs::WindowBorderSize> {
         let mut r2 = get_rect_struct();
 
         unsafe {
-            if DwmGetWindowAttribute(
-                self.get_platform_specific_handle(),
+            DwmGetWindowAttribute(
+                self.get_hwnd(),
                 DWMWA_EXTENDED_FRAME_BOUNDS,
                 &mut r2 as *mut _ as *mut _,
                 u32::try_from(size_of::<RECT>()).unwrap(),
             )
-            .is_err()
-            {
-                error!("get_margin: Error from DwmGetWindowAttribute");
-            }
-        };
+        }
+        .map_err(|error| self.error("DwmGetWindowAttribute", error))?;
 
-        let r = self.get_position();
+        let r = self.get_position()?;
         let margin = WindowBorderSize {
             left: r.left - r2.left,
             right: r.right - r2.right,
@@ -154,19 +159,24 @@ impl Window for WindowsWindow {
             bottom: r.bottom - r2.bottom,
         };
         info!("get_margin: margin: {:?}", margin);
-        margin
+        Ok(margin)
     }
 
-    fn get_current_monitor(&self) -> Box<dyn Monitor> {
+    fn get_current_monitor(&self) -> Result<Box<dyn Monitor>> {
         let monitor;
         unsafe {
-            monitor = MonitorFromWindow(
-                self.get_platform_specific_handle(),
-                MONITOR_DEFAULTTONEAREST,
-            );
+            monitor = MonitorFromWindow(self.get_hwnd(), MONITOR_DEFAULTTONEAREST);
         }
         info!("get_current_monitor: handle: {:?}", monitor);
-        Box::new(WindowsMonitor::new(monitor))
+        // Only an invalid window has no nearest monitor
+        if monitor.is_invalid() {
+            return Err(Error::WindowNotFound(self.platform_specific_handle));
+        }
+        Ok(Box::new(WindowsMonitor::new(monitor)))
+    }
+
+    fn get_platform_specific_handle(&self) -> isize {
+        self.platform_specific_handle
     }
 }
 